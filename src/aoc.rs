@@ -1,29 +1,151 @@
-pub(crate) mod grid;
-pub(crate) mod maze;
-pub(crate) mod sep;
-
-pub(crate) mod day_01;
-pub(crate) mod day_02;
-pub(crate) mod day_03;
-pub(crate) mod day_04;
-pub(crate) mod day_05;
-pub(crate) mod day_06;
-pub(crate) mod day_07;
-pub(crate) mod day_08;
-pub(crate) mod day_09;
-pub(crate) mod day_10;
-pub(crate) mod day_11;
-pub(crate) mod day_12;
-pub(crate) mod day_13;
-pub(crate) mod day_14;
-pub(crate) mod day_15;
-pub(crate) mod day_16;
-pub(crate) mod day_17;
-pub(crate) mod day_18;
-pub(crate) mod day_19;
-pub(crate) mod day_20;
-pub(crate) mod day_21;
-pub(crate) mod day_22;
-pub(crate) mod day_23;
-pub(crate) mod day_24;
-pub(crate) mod day_25;
+//! The days of Advent of Code 2024 and their shared helpers.
+
+pub mod graph;
+pub mod grid;
+pub mod maze;
+pub mod parse;
+pub mod runner;
+pub mod search;
+pub mod sep;
+
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod day_06;
+pub mod day_07;
+pub mod day_08;
+pub mod day_09;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod day_19;
+pub mod day_20;
+pub mod day_21;
+pub mod day_22;
+pub mod day_23;
+pub mod day_24;
+pub mod day_25;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use parse::ParseError;
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Enables the diagnostics some days print to stderr while solving.
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// Whether diagnostics are enabled, see [`set_verbose`].
+pub fn is_verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+// prints to stderr when diagnostics are enabled, stdout is kept for answers
+macro_rules! diagnostic {
+    ($($arg:tt)*) => {
+        if $crate::aoc::is_verbose() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use diagnostic;
+
+/// Answer of a part, anything that can be displayed.
+pub type Answer = Box<dyn std::fmt::Display>;
+
+/// A parsed puzzle input that can answer both parts of its day.
+///
+/// Parts that are not implemented yet keep the default and answer `None`,
+/// parts that cannot answer for this input give why as an error.
+pub trait Solution {
+    /// Parses the puzzle input.
+    fn parse(data: &str) -> Result<Self, ParseError> where Self: Sized;
+
+    /// Same as [`Solution::parse`], panics on invalid input.
+    fn load(data: &str) -> Self where Self: Sized {
+        Self::parse(data).unwrap_or_else(|e| panic!("invalid input at {e}"))
+    }
+
+    /// Answer of part 1, `None` when not implemented.
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(None)
+    }
+
+    /// Answer of part 2, `None` when not implemented.
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(None)
+    }
+}
+
+/// A day of the calendar and the parser of its puzzle.
+pub struct Day {
+    /// Day of the month, from 1 to 25.
+    pub day: u8,
+    /// Title of the puzzle.
+    pub title: &'static str,
+    parse: fn(&str) -> Result<Box<dyn Solution>, ParseError>
+}
+
+impl Day {
+    const fn new<S: Solution + 'static>(day: u8, title: &'static str) -> Self {
+        Self {day, title, parse: parse::<S>}
+    }
+
+    /// Parses `data` with the solution of this day.
+    pub fn parse(&self, data: &str) -> Result<Box<dyn Solution>, ParseError> {
+        (self.parse)(data)
+    }
+}
+
+fn parse<S: Solution + 'static>(data: &str) -> Result<Box<dyn Solution>, ParseError> {
+    Ok(Box::new(S::parse(data)?))
+}
+
+static DAYS: [Day; 25] = [
+    Day::new::<day_01::Puzzle>(1, "Historian Hysteria"),
+    Day::new::<day_02::Puzzle>(2, "Red-Nosed Reports"),
+    Day::new::<day_03::Puzzle>(3, "Mull It Over"),
+    Day::new::<day_04::Puzzle>(4, "Ceres Search"),
+    Day::new::<day_05::Puzzle>(5, "Print Queue"),
+    Day::new::<day_06::Puzzle>(6, "Guard Gallivant"),
+    Day::new::<day_07::Puzzle>(7, "Bridge Repair"),
+    Day::new::<day_08::Puzzle>(8, "Resonant Collinearity"),
+    Day::new::<day_09::Puzzle>(9, "Disk Fragmenter"),
+    Day::new::<day_10::Puzzle>(10, "Hoof It"),
+    Day::new::<day_11::Puzzle>(11, "Plutonian Pebbles"),
+    Day::new::<day_12::Puzzle>(12, "Garden Groups"),
+    Day::new::<day_13::Puzzle>(13, "Claw Contraption"),
+    Day::new::<day_14::Puzzle>(14, "Restroom Redoubt"),
+    Day::new::<day_15::Puzzle>(15, "Warehouse Woes"),
+    Day::new::<day_16::Data>(16, "Reindeer Maze"),
+    Day::new::<day_17::Computer>(17, "Chronospatial Computer"),
+    Day::new::<day_18::Puzzle>(18, "RAM Run"),
+    Day::new::<day_19::Data>(19, "Linen Layout"),
+    Day::new::<day_20::Puzzle>(20, "Race Condition"),
+    Day::new::<day_21::Puzzle>(21, "Keypad Conundrum"),
+    Day::new::<day_22::Puzzle>(22, "Monkey Market"),
+    Day::new::<day_23::Puzzle>(23, "LAN Party"),
+    Day::new::<day_24::Puzzle>(24, "Crossed Wires"),
+    Day::new::<day_25::Puzzle>(25, "Code Chronicle")
+];
+
+/// All the days, in order.
+pub fn days() -> &'static [Day] {
+    &DAYS
+}
+
+/// The given day, if it exists.
+pub fn get(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
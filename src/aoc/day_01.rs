@@ -1,25 +1,36 @@
+//! Day 1: Historian Hysteria.
+
 use std::collections::HashMap;
 
-struct Puzzle {
+use crate::aoc::{Answer, Solution, parse::{self, ParseError}};
+
+/// The two lists of location ids.
+pub struct Puzzle {
     locations: Vec<(u32, u32)>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let parse_locations = |s: &str| {
             let mut vs = s.split_ascii_whitespace();
-            let v0 = vs.next()?.parse().ok()?;
-            let v1 = vs.next()?.parse().ok()?;
-            if vs.next().is_some() {return None;}
-            Some((v0, v1))
+            let mut next = || parse::value(data, parse::next(data, s, &mut vs, "a location id")?, "a location id");
+            let (v0, v1) = (next()?, next()?);
+            if let Some(v) = vs.next() {
+                return Err(ParseError::at(data, v, "end of line"));
+            }
+            Ok((v0, v1))
         };
-        let locations: Option<_> = data.trim().lines().map(parse_locations).collect();
+        let locations: Result<_, _> = data.trim().lines().map(parse_locations).collect();
         let locations = locations?;
-        Some(Puzzle {locations})
+        Ok(Puzzle {locations})
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
+    }
+
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
 }
 
@@ -27,7 +38,7 @@ fn part_1(puzzle: &Puzzle) -> u32 {
     let (mut v0s, mut v1s): (Vec<_>, Vec<_>) = puzzle.locations.iter().cloned().unzip();
     v0s.sort();
     v1s.sort();
-    v0s.into_iter().zip(v1s.into_iter()).map(|(v0, v1)| if v0 > v1 {v0 - v1} else {v1 - v0}).sum()
+    v0s.into_iter().zip(v1s).map(|(v0, v1)| v0.abs_diff(v1)).sum()
 }
 
 fn part_2(puzzle: &Puzzle) -> u32 {
@@ -38,23 +49,26 @@ fn part_2(puzzle: &Puzzle) -> u32 {
     let mut distance = 0;
     for (v0, _) in &puzzle.locations {
         if v2.contains_key(v0) {
-            distance += v2[&v0] * v0;
+            distance += v2[v0] * v0;
         }
     }
     distance
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_01/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse() {
+        let e = Puzzle::parse("3   4\n4   3   2\n").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str(), e.expected.as_str()), (2, 9, "2", "end of line"));
+        let e = Puzzle::parse("3   4\n4\n").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, ""));
+        let e = Puzzle::parse("3   4\n4   x\n").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 5, "x"));
+    }
+
     #[test]
     fn test_part_1() {
         let data = include_str!("../../data/day_01/test.txt");
@@ -1,23 +1,32 @@
-struct Puzzle {
+//! Day 2: Red-Nosed Reports.
+
+use crate::aoc::{Answer, Solution, parse::{self, ParseError}};
+
+/// The reports, a list of levels each.
+pub struct Puzzle {
     reports: Vec<Vec<u32>>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let parse_nums = |s: &str| {
-            s.trim().split_ascii_whitespace().map(|s| s.parse()).collect()
+            s.trim().split_ascii_whitespace().map(|s| parse::value(data, s, "a level")).collect()
         };
-        let reports: Result<_, _> = data.lines().map(parse_nums).collect();
-        let reports = reports.ok()?;
-        Some(Puzzle {reports})
+        let reports: Result<_, ParseError> = data.lines().map(parse_nums).collect();
+        let reports = reports?;
+        Ok(Puzzle {reports})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
 }
 
-fn is_safe(levels: &Vec<u32>) -> bool {
+fn is_safe(levels: &[u32]) -> bool {
     if levels.len() < 2 {
         return true;
     }
@@ -39,20 +48,13 @@ fn part_2(puzzle: &Puzzle) -> usize {
     puzzle.reports.iter().filter(|ls| {
         is_safe(ls) || {
             (0..ls.len()).any(|r| {
-                let vs = (0..r).chain((r + 1)..ls.len()).map(|i| ls[i]).collect();
+                let vs: Vec<_> = (0..r).chain((r + 1)..ls.len()).map(|i| ls[i]).collect();
                 is_safe(&vs)
             })
         }
     }).count()
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_02/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,11 +1,26 @@
-struct Puzzle {
+//! Day 3: Mull It Over.
+
+use crate::aoc::{Answer, Solution, parse::ParseError};
+
+/// The corrupted memory.
+pub struct Puzzle {
     memory: String
 }
 
-impl Puzzle {
-    fn load(data: &str) -> Puzzle {
-        assert!(data.is_ascii());
-        Self {memory: data.to_string()}
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        if let Some((i, c)) = data.char_indices().find(|(_, c)| !c.is_ascii()) {
+            return Err(ParseError::at(data, &data[i..(i + c.len_utf8())], "an ascii character"));
+        }
+        Ok(Self {memory: data.to_string()})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
+    }
+
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
 }
 
@@ -17,15 +32,15 @@ fn part_1(puzzle: &Puzzle) -> u64 {
         if let Some(e) = s.find(')') {
             let s = &s[..e];
             if s.len() > 7 {continue;}
-            let mut ns = s.split(|c| c as char == ',');
+            let mut ns = s.split(',');
             let a = ns.next();
             let b = ns.next();
             if a.is_none() || b.is_none() {continue;}
             if ns.next().is_some() {continue;}
             let a = a.unwrap();
             let b = b.unwrap();
-            let a = u64::from_str_radix(a, 10);
-            let b = u64::from_str_radix(b, 10);
+            let a = a.parse::<u64>();
+            let b = b.parse::<u64>();
             if a.is_err() || b.is_err() {continue;}
             let a = a.unwrap();
             let b = b.unwrap();
@@ -51,21 +66,20 @@ fn part_2(puzzle: &Puzzle) -> u64 {
         } else if s.starts_with(DONT) {
             b += DONT.len();
             enable = false;
-        } else if s.starts_with(MUL) {
+        } else if let Some(s) = s.strip_prefix(MUL) {
             b += MUL.len();
-            let s = &s[MUL.len()..];
             if let Some(e) = s.find(')') {
                 let s = &s[..e];
                 if s.len() > 7 {continue;}
-                let mut ns = s.split(|c| c as char == ',');
+                let mut ns = s.split(',');
                 let n1 = ns.next();
                 let n2 = ns.next();
                 if n1.is_none() || n2.is_none() {continue;}
                 if ns.next().is_some() {continue;}
                 let n1 = n1.unwrap();
                 let n2 = n2.unwrap();
-                let n1 = u64::from_str_radix(n1, 10);
-                let n2 = u64::from_str_radix(n2, 10);
+                let n1 = n1.parse::<u64>();
+                let n2 = n2.parse::<u64>();
                 if n1.is_err() || n2.is_err() {continue;}
                 let n1 = n1.unwrap();
                 let n2 = n2.unwrap();
@@ -81,13 +95,6 @@ fn part_2(puzzle: &Puzzle) -> u64 {
     total
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_03/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
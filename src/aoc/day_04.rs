@@ -1,21 +1,44 @@
-struct Puzzle {
+//! Day 4: Ceres Search.
+
+use crate::aoc::{Answer, Solution, grid::Direction8, parse::ParseError};
+
+/// The word search.
+pub struct Puzzle {
     letters: Vec<String>,
     size: (usize, usize),
 }
 
-impl Puzzle {
-    fn load(data: &str) -> Self {
-        let letters: Vec<_> = data.lines().map(|s| s.to_string()).collect();
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let letters: Vec<_> = data.trim().lines().map(|s| s.trim()).collect();
+        if letters.is_empty() || letters[0].is_empty() {
+            return Err(ParseError::at(data, data.trim(), "a grid of letters"));
+        }
+        if let Some(l) = letters.iter().find(|l| !l.is_ascii() || (l.len() != letters[0].len())) {
+            return Err(ParseError::at(data, l, &format!("a line of {} letters", letters[0].len())));
+        }
+        let letters: Vec<_> = letters.into_iter().map(String::from).collect();
         let size = (letters[0].len(), letters.len());
-        Puzzle {letters, size}
+        Ok(Puzzle {letters, size})
     }
 
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
+    }
+
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
+    }
+}
+
+impl Puzzle {
     fn get(&self, r: usize, c: usize) -> char {
         self.letters[r].as_bytes()[c] as char
     }
 }
 
-struct PuzzleIterator<'a> {
+/// The lines of the word search in all the directions.
+pub struct PuzzleIterator<'a> {
     data: &'a Puzzle,
     position: usize,
     direction: usize
@@ -30,21 +53,9 @@ impl<'a> PuzzleIterator<'a> {
     fn next_position(&mut self) {
         self.position += 1;
         match self.direction {
-            0 => {
-                if self.position == self.data.size.1 {
-                    self.next_direction();
-                }
-            }
-            1 => {
-                if self.position == self.data.size.0 {
-                    self.next_direction();
-                }
-            }
-            2..=3 => {
-                if self.position == ((self.data.size.0 + self.data.size.1) - 1) {
-                    self.next_direction();
-                }
-            }
+            0 if self.position == self.data.size.1 => self.next_direction(),
+            1 if self.position == self.data.size.0 => self.next_direction(),
+            2..=3 if self.position == ((self.data.size.0 + self.data.size.1) - 1) => self.next_direction(),
             _ => ()
         }
     }
@@ -132,13 +143,12 @@ fn part_2(puzzle: &Puzzle) -> u32 {
 
     for r in br..er {
         for c in bc..ec {
-            let mrmc = puzzle.get(r - 1, c - 1);
-            let mrpc = puzzle.get(r - 1, c + 1);
-            let prmc = puzzle.get(r + 1, c - 1);
-            let prpc = puzzle.get(r + 1, c + 1);
-            if puzzle.get(r, c) == 'A'
-                && ((mrmc == 'M' && prpc == 'S') || (mrmc == 'S' && prpc == 'M'))
-                && ((mrpc == 'M' && prmc == 'S') || (mrpc == 'S' && prmc == 'M')) {
+            let at = |d: Direction8| {
+                let o = d.offset();
+                puzzle.get((r as i64 + o.y) as usize, (c as i64 + o.x) as usize)
+            };
+            let mas = |d: Direction8| matches!((at(d), at(d.opposite())), ('M', 'S') | ('S', 'M'));
+            if (puzzle.get(r, c) == 'A') && mas(Direction8::NorthWest) && mas(Direction8::NorthEast) {
                 total += 1;
             }
         }
@@ -146,13 +156,6 @@ fn part_2(puzzle: &Puzzle) -> u32 {
     total
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_04/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
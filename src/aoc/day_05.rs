@@ -1,41 +1,51 @@
+//! Day 5: Print Queue.
+
 use std::{cmp::Ordering, collections::HashMap};
 
-struct Puzzle {
+use crate::aoc::{Answer, Solution, parse::{self, ParseError}};
+
+/// The page ordering rules and the updates.
+pub struct Puzzle {
     rules: HashMap<u32, Vec<u32>>,
     updates: Vec<Vec<u32>>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option <Self> {
-        let data: Vec<_> = data.trim().lines().map(|s| s.trim()).collect();
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let lines: Vec<_> = data.trim().lines().map(|s| s.trim()).collect();
         let (order, updates) = {
-            let s = data.iter().position(|s| s.is_empty())?;
-            (data[..s].iter(), data[(s + 1)..].iter())
+            let s = lines.iter().position(|s| s.is_empty());
+            let s = s.ok_or_else(|| ParseError::after(data, data.trim(), "an empty line before the updates"))?;
+            (lines[..s].iter(), lines[(s + 1)..].iter())
         };
 
         let mut rules = HashMap::new();
-        for update in order {
-            let (p1, p2) = update.split_once('|')?;
-            let (p1, p2) = (p1.parse().ok()?, p2.parse().ok()?);
+        for rule in order {
+            let (p1, p2) = parse::split_once(data, rule, "|")?;
+            let (p1, p2) = (parse::value(data, p1, "a page number")?, parse::value(data, p2, "a page number")?);
             rules.entry(p1).or_insert(Vec::new()).push(p2);
         }
 
         let parse_update = |ps: &&str| {
-            let ps: Option<Vec<_>> = ps.split(',').map(|p| p.parse().ok()).collect();
-            Some(ps?)
+            let ps: Result<Vec<_>, _> = ps.split(',').map(|p| parse::value(data, p, "a page number")).collect();
+            ps
         };
-        let updates: Option<Vec<_>> = updates.map(parse_update).collect();
+        let updates: Result<Vec<_>, _> = updates.map(parse_update).collect();
         let updates = updates?;
 
-        Some(Puzzle {rules, updates})
+        Ok(Puzzle {rules, updates})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
 }
 
-fn is_valid_update(update: &Vec<u32>, rules: &HashMap<u32, Vec<u32>>) -> bool {
+fn is_valid_update(update: &[u32], rules: &HashMap<u32, Vec<u32>>) -> bool {
     update.iter().enumerate().all(|(i, p)| {
         !rules.get(p).is_some_and(|rs| {rs.iter().any(|p| update[..i].contains(p))})
     })
@@ -64,13 +74,6 @@ fn part_2(data: &Puzzle) -> u32 {
         .sum()
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_05/input.txt"); // 6951 / 4121
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,6 @@
-use crate::aoc::grid::Grid;
+//! Day 6: Guard Gallivant.
+
+use crate::aoc::{Answer, Solution, grid::{Direction, Grid, Point}, parse::ParseError};
 
 #[derive(Eq, PartialEq)]
 enum Step {
@@ -10,48 +12,39 @@ enum Step {
 
 type Position = (i32, i32, char);
 
+/// The lab map and the guard.
 #[derive(Clone)]
-struct Puzzle {
+pub struct Puzzle {
     map: Grid<char>,
     position: Option<Position>
 }
 
-impl Puzzle {
-    fn new(data: &str) -> Self {
-        let map = data.trim().lines().map(|line| line.trim().chars().collect::<Vec<_>>()).collect::<Vec<_>>();
-        let map = Grid::from_vec(&map);
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let map: Grid<char> = Grid::parse(data, "")?;
         let position = map.find_by(|c| "^v<>".contains(*c));
-        let position = position.and_then(|(x, y)| Some((x as i32, y as i32, map.get((x, y)))));
-        Self {map, position}
+        let position = position.map(|(x, y)| (x as i32, y as i32, map.get((x, y))));
+        Ok(Self {map, position})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(&mut self.clone()))))
+    }
+
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(&mut self.clone()))))
     }
+}
 
+impl Puzzle {
     fn next(&self) -> Option<Position> {
         self.position.and_then(|(x, y, d)| {
-            let (nx, ny) = match d {
-                '^' => (x, y - 1),
-                '>' => (x + 1, y),
-                'v' => (x, y + 1),
-                '<' => (x - 1, y),
-                _ => panic!("invalid direction")
-            };
-            let (sx, sy) = self.map.size();
-            let (sx, sy) = (sx as i32, sy as i32);
-            if (nx >= 0) && (nx < sx) && (ny >= 0) && (ny < sy) {
-                let c = self.map.get((nx as usize, ny as usize));
-                if c == '#' {
-                    let nd = match d {
-                        '^' => '>',
-                        '>' => 'v',
-                        'v' => '<',
-                        '<' => '^',
-                        _ => panic!("invalid direction")
-                    };
-                    Some((x, y, nd))
-                } else {
-                    Some((nx, ny, d))
-                }
+            let direction = Direction::try_from(d).expect("valid direction");
+            let n = Point::new(x as i64, y as i64) + direction.offset();
+            if self.map.get_checked(n)? == '#' {
+                Some((x, y, char::from(direction.turn_right())))
             } else {
-                None
+                Some((n.x as i32, n.y as i32, d))
             }
         })
     }
@@ -77,27 +70,11 @@ impl Puzzle {
             }
         }
     }
-
-    fn reset(&mut self, position: Option<Position>) {
-        let (w, h) = self.map.size();
-        for y in 0..h {
-            for x in 0..w {
-                let p = (x, y);
-                if self.map.get(p) != '#' {
-                    self.map.set(p, '.');
-                }
-            }
-        }
-        if let Some((x, y, d)) = position {
-            self.map.set((x as usize, y as usize), d);
-        }
-        self.position = position;
-    }
 }
 
 fn part_1(puzzle: &mut Puzzle) -> u32 {
     let mut total = 1u32;
-    while !puzzle.position.is_none() {
+    while puzzle.position.is_some() {
         if puzzle.step() == Step::New {total += 1;}
     }
     total
@@ -105,12 +82,12 @@ fn part_1(puzzle: &mut Puzzle) -> u32 {
 
 fn part_2(puzzle: &mut Puzzle) -> u32 {
     let mut total = 0;
-    while !puzzle.position.is_none() {
+    while puzzle.position.is_some() {
         let mut test = puzzle.clone();
         if let Some((x, y, _)) = test.next() {
             if test.map.get((x as usize, y as usize)) == '.' {
                 test.map.set((x as usize, y as usize), '#');
-                while !test.position.is_none() {
+                while test.position.is_some() {
                     if test.step() == Step::Loop {
                         total += 1;
                         break;
@@ -123,15 +100,7 @@ fn part_2(puzzle: &mut Puzzle) -> u32 {
     total
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_06/input.txt");
-    let mut puzzle = Puzzle::new(data);
-    let start = puzzle.position;
-    println!("part 1: {}", part_1(&mut puzzle));
-    puzzle.reset(start);
-    println!("part 2: {}", part_2(&mut puzzle));
-}
-
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -139,13 +108,13 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let mut puzzle = Puzzle::new(DATA);
+        let mut puzzle = Puzzle::load(DATA);
         assert_eq!(part_1(&mut puzzle), 41);
     }
 
     #[test]
     fn test_part_2() {
-        let mut puzzle = Puzzle::new(DATA);
+        let mut puzzle = Puzzle::load(DATA);
         assert_eq!(part_2(&mut puzzle), 6);
     }
 }
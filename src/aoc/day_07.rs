@@ -1,3 +1,7 @@
+//! Day 7: Bridge Repair.
+
+use crate::aoc::{Answer, Solution, parse::{self, ParseError}};
+
 #[derive(Clone, Copy)]
 enum Op {
     Add,
@@ -52,26 +56,31 @@ impl Equation {
     }
 }
 
-struct Puzzle {
+/// The calibration equations.
+pub struct Puzzle {
     equations: Vec<Equation>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let parse_equation = |s: &str| {
-            let (result, numbers) = s.trim().split_once(": ")?;
-            let result = result.parse().ok()?;
-            let numbers: Option<Vec<_>> = numbers.split(' ').map(|v| v.parse().ok()).collect();
+            let (result, numbers) = parse::split_once(data, s.trim(), ": ")?;
+            let result = parse::value(data, result, "a test value")?;
+            let numbers: Result<Vec<_>, _> = numbers.split(' ').map(|v| parse::value(data, v, "a number")).collect();
             let numbers = numbers?;
-            Some(Equation {result, numbers})
+            Ok(Equation {result, numbers})
         };
-        let equations: Option<Vec<_>> = data.trim().lines().map(parse_equation).collect();
+        let equations: Result<Vec<_>, _> = data.trim().lines().map(parse_equation).collect();
         let equations = equations?;
-        Some(Puzzle {equations})
+        Ok(Puzzle {equations})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
 }
 
@@ -89,13 +98,7 @@ fn part_2(puzzle: &Puzzle) -> u64 {
         .sum()
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_07/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -1,93 +1,75 @@
+//! Day 8: Resonant Collinearity.
+
 use std::collections::{HashMap, HashSet};
-use crate::aoc::grid::Grid;
+use crate::aoc::{Answer, Solution, grid::{Grid, Point}, parse::ParseError};
 
-struct Puzzle {
+/// The antenna map.
+pub struct Puzzle {
     map: Grid<char>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let map = Grid::parse(data, "")?;
-        Some(Self {map})
+        Ok(Self {map})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_2(self))))
     }
 }
 
 fn solve_part_1(puzzle: &Puzzle) -> usize {
-    let (width, height) = puzzle.map.size();
-    let (width, height) = (width as i32, height as i32);
     let mut antennas = HashMap::new();
     let mut anti_nodes = HashSet::new();
-    for y in 0..height {
-        for x in 0..width {
-            let c = puzzle.map.get((x as usize, y as usize));
-            if c != '.' {
-                let antennas = antennas.entry(c).or_insert(Vec::new());
-                for (ax, ay) in antennas.iter() {
-                    let (dx, dy) = (x - ax, y - ay);
-                    let (nx, ny) = (ax - dx, ay - dy);
-                    if (nx >= 0) && (nx < width) && (ny >= 0) && (ny < height) {
-                        anti_nodes.insert((nx, ny));
-                    }
-                    let (nx, ny) = (x + dx, y + dy);
-                    if (nx >= 0) && (nx < width) && (ny >= 0) && (ny < height) {
-                        anti_nodes.insert((nx, ny));
+    for ((x, y), c) in puzzle.map.cells() {
+        if c != '.' {
+            let p = Point::new(x as i64, y as i64);
+            let antennas = antennas.entry(c).or_insert(Vec::new());
+            for a in antennas.iter() {
+                let d = p - *a;
+                for n in [*a - d, p + d] {
+                    if puzzle.map.index(n).is_some() {
+                        anti_nodes.insert(n);
                     }
                 }
-                antennas.push((x, y));
             }
+            antennas.push(p);
         }
     }
     anti_nodes.len()
 }
 
 fn solve_part_2(puzzle: &Puzzle) -> usize {
-    let (width, height) = puzzle.map.size();
-    let (width, height) = (width as i32, height as i32);
     let mut antennas = HashMap::new();
     let mut anti_nodes = HashSet::new();
-    for y in 0..height {
-        for x in 0..width {
-            let c = puzzle.map.get((x as usize, y as usize));
-            if c != '.' {
-                let antennas = antennas.entry(c).or_insert(Vec::new());
-                for (ax, ay) in antennas.iter() {
-                    let (dx, dy) = (x - ax, y - ay);
-                    for i in 0.. {
-                        let (nx, ny) = (ax - dx * i, ay - dy * i);
-                        let d1 = if (nx >= 0) && (nx < width) && (ny >= 0) && (ny < height) {
-                            anti_nodes.insert((nx, ny));
-                            false
-                        } else {
-                            true
-                        };
-                        let (nx, ny) = (x + dx * i, y + dy * i);
-                        let d2 = if (nx >= 0) && (nx < width) && (ny >= 0) && (ny < height) {
-                            anti_nodes.insert((nx, ny));
-                            false
-                        } else {
-                            true
-                        };
-                        if d1 && d2 {break;}
+    for ((x, y), c) in puzzle.map.cells() {
+        if c != '.' {
+            let p = Point::new(x as i64, y as i64);
+            let antennas = antennas.entry(c).or_insert(Vec::new());
+            for a in antennas.iter() {
+                let d = p - *a;
+                for i in 0.. {
+                    let mut done = true;
+                    for n in [*a - d * i, p + d * i] {
+                        if puzzle.map.index(n).is_some() {
+                            anti_nodes.insert(n);
+                            done = false;
+                        }
                     }
+                    if done {break;}
                 }
-                antennas.push((x, y));
             }
+            antennas.push(p);
         }
     }
     anti_nodes.len()
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_08/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", solve_part_1(&puzzle));
-    println!("part 2: {}", solve_part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
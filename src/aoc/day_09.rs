@@ -1,29 +1,41 @@
-struct Puzzle {
+//! Day 9: Disk Fragmenter.
+
+use crate::aoc::{Answer, Solution, parse::ParseError};
+
+/// The disk map, expanded into blocks.
+pub struct Puzzle {
     fs: Vec<u32>,
     files: Vec<(usize, usize)>,
     spaces: Vec<(usize, usize)>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let mut fs = Vec::new();
         let mut files = Vec::new();
         let mut spaces = Vec::new();
-        for (i, c) in data.trim().chars().enumerate() {
-            let n = c.to_digit(10)?;
+        let disk = data.trim();
+        // all the characters before a valid one are digits, byte and char indices match
+        for (i, c) in disk.char_indices() {
+            let n = c.to_digit(10);
+            let n = n.ok_or_else(|| ParseError::at(data, &disk[i..(i + c.len_utf8())], "a digit"))?;
             let id = if (i & 1) == 0 {(i / 2) as u32} else {u32::MAX};
             (if id != u32::MAX {&mut files} else {&mut spaces}).push((fs.len(), n as usize));
-            fs.extend(std::iter::repeat(id).take(n as usize));
+            fs.extend(std::iter::repeat_n(id, n as usize));
         }
-        Some(Self {fs, files, spaces})
+        Ok(Self {fs, files, spaces})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_2(self))))
     }
 }
 
-fn checksum(fs: &Vec<u32>) -> u64 {
+fn checksum(fs: &[u32]) -> u64 {
     fs.iter().enumerate()
              .filter(|(_, id)| **id != u32::MAX)
              .map(|(i, id)| (i as u64) * (*id as u64))
@@ -69,13 +81,6 @@ fn solve_part_2(puzzle: &Puzzle) -> u64 {
     checksum(&fs)
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_09/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", solve_part_1(&puzzle));
-    println!("part 2: {}", solve_part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,34 +1,40 @@
+//! Day 10: Hoof It.
+
 use std::collections::HashSet;
-use crate::aoc::grid::Grid;
+use crate::aoc::{Answer, Solution, grid::{Grid, Point}, parse::ParseError};
 
-struct Puzzle {
+/// The topographic map.
+pub struct Puzzle {
     map:Grid<u8>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let map= Grid::parse(data, "")?;
-        Some(Self {map})
+        Ok(Self {map})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
+}
 
+impl Puzzle {
     fn score(&self, x: usize, y: usize) -> u32 {
         if self.map.get((x, y)) != 0 {return 0;}
-        let (w, h) = self.map.size();
-        let (w, h) = (w as i32, h as i32);
         let mut ps = HashSet::new();
-        ps.insert((x as i32, y as i32));
+        ps.insert(Point::new(x as i64, y as i64));
         for z in 1..=9 {
             let mut nps = HashSet::new();
-            for (x, y) in &ps {
-                let (x, y) = (*x, *y);
-                for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-                    let (nx, ny) = (x + dx, y + dy);
-                    if (nx >= 0) && (nx < w as i32) && (ny >= 0) && (ny < h as i32) && (self.map.get((nx as usize, ny as usize)) == z) {
-                        nps.insert((nx, ny));
+            for p in &ps {
+                for d in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    let n = *p + d.into();
+                    if self.map.get_checked(n) == Some(z) {
+                        nps.insert(n);
                     }
                 }
             }
@@ -39,18 +45,14 @@ impl Puzzle {
 
     fn rating(&self, x: usize, y: usize) -> u32 {
         if self.map.get((x, y)) != 9 {return 0;}
-        let (w, h) = self.map.size();
-        let (w, h) = (w as i32, h as i32);
-        let mut ps = Vec::new();
-        ps.push((x as i32, y as i32));
+        let mut ps = vec![Point::new(x as i64, y as i64)];
         for z in (0..=8).rev() {
             let mut nps = Vec::new();
-            for (x, y) in &ps {
-                let (x, y) = (*x, *y);
-                for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-                    let (nx, ny) = (x + dx, y + dy);
-                    if (nx >= 0) && (nx < w as i32) && (ny >= 0) && (ny < h as i32) && (self.map.get((nx as usize, ny as usize)) == z) {
-                        nps.push((nx, ny));
+            for p in &ps {
+                for d in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    let n = *p + d.into();
+                    if self.map.get_checked(n) == Some(z) {
+                        nps.push(n);
                     }
                 }
             }
@@ -68,7 +70,7 @@ fn part_1(puzzle: &Puzzle) -> u32 {
             total += puzzle.score(x, y);
         }
     }
-    return total;
+    total
 }
 
 fn part_2(puzzle: &Puzzle) -> u32 {
@@ -79,14 +81,7 @@ fn part_2(puzzle: &Puzzle) -> u32 {
             total += puzzle.rating(x, y);
         }
     }
-    return total;
-}
-
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_10/input.txt");
-    let data = Puzzle::load(data);
-    println!("part 1: {}", part_1(&data));
-    println!("part 2: {}", part_2(&data));
+    total
 }
 
 #[cfg(test)]
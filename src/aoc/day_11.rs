@@ -1,21 +1,31 @@
+//! Day 11: Plutonian Pebbles.
+
 use std::collections::HashMap;
 
-struct Puzzle {
+use crate::aoc::{Answer, Solution, parse::{self, ParseError}};
+
+/// The numbers engraved on the stones.
+pub struct Puzzle {
     stones: Vec<u64>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option <Self> {
-        let stones: Option<Vec<_>> = data.trim().split(' ').map(|s| s.parse().ok()).collect();
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let stones: Result<Vec<_>, _> = data.trim().split(' ').map(|s| parse::value(data, s, "a number")).collect();
         let stones = stones?;
-        Some(Self {stones})
+        Ok(Self {stones})
     }
 
-    
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
+    }
+}
+
+impl Puzzle {
     fn blink(&self, blinks: u8) -> u64 {
         let mut ss = self.stones.iter().fold(HashMap::new(), |mut m, v| {*m.entry(*v).or_insert(0) += 1; m});
         for _ in 0..blinks {
@@ -36,7 +46,7 @@ impl Puzzle {
             }
             ss = nss;
         }
-        ss.values().fold(0, |t, n| t + n)
+        ss.values().sum()
     }
 }
 
@@ -48,13 +58,6 @@ fn part_2(puzzle: &Puzzle) -> u64 {
     puzzle.blink(75)
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_11/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
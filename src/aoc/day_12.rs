@@ -1,20 +1,27 @@
+//! Day 12: Garden Groups.
+
 use std::collections::HashSet;
-use crate::aoc::grid::Grid;
+use crate::aoc::{Answer, Solution, grid::{Direction, Grid, Point}, parse::ParseError};
 
 const DBG: bool = cfg!(debug_assertions);
 
-struct Puzzle {
+/// The garden plots map.
+pub struct Puzzle {
     map: Grid<char>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let map = Grid::parse(data, "")?;
-        Some(Self {map})
+        Ok(Self {map})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
 }
 
@@ -27,12 +34,12 @@ struct Region {
 }
 
 fn get_regions(puzzle: &Puzzle) -> Vec<Region> {
-    let (iw, ih) = {let (w, h) = puzzle.map.size(); (w as i32, h as i32)};
+    let (iw, ih) = {let (w, h) = puzzle.map.size(); (w as i64, h as i64)};
 
     struct R {
         name: char,
-        cells: Vec<(i32, i32)>,
-        borders: Vec<(char, i32, i32)>
+        cells: Vec<(i64, i64)>,
+        borders: Vec<(Direction, i64, i64)>
     }
 
     let mut rs = Vec::new();
@@ -45,13 +52,12 @@ fn get_regions(puzzle: &Puzzle) -> Vec<Region> {
                 let mut r = R {name: n, cells: Vec::new(), borders: Vec::new()};
                 let mut s = Vec::new();
                 s.push((x, y));
-                while !s.is_empty() {
-                    let (x, y) = s.pop().unwrap();
+                while let Some((x, y)) = s.pop() {
                     r.cells.push((x, y));
-                    for d in ['<', '>', '^', 'v'] {
-                        let (dx, dy) = get_offset(d);
-                        let (nx, ny) = (x + dx, y + dy);
-                        if (nx >= 0) && (nx < iw) && (ny >= 0) && (ny < ih) && (puzzle.map.get((nx as usize, ny as usize)) == n) {
+                    for d in Direction::ALL {
+                        let np = Point::new(x, y) + d.offset();
+                        if puzzle.map.get_checked(np) == Some(n) {
+                            let (nx, ny) = np.into();
                             if !vs.contains(&(nx, ny)) {
                                 vs.insert((nx, ny));
                                 s.push((nx, ny));
@@ -70,10 +76,10 @@ fn get_regions(puzzle: &Puzzle) -> Vec<Region> {
         let name = r.name;
         let area = r.cells.len() as u32;
         let perimeter = r.borders.len() as u32;
-        let edges = ['<', '>', '^', 'v'].iter().map(|d| {
+        let edges = Direction::ALL.iter().map(|d| {
             let mut count = 0;
-            let (dx, dy) = get_offset(*d);
-            let (dx, dy) = (dy.abs(), dx.abs());
+            // borders facing `d` are followed along the perpendicular direction
+            let (dx, dy) = if d.is_horizontal() {(0, 1)} else {(1, 0)};
             if dx == 0 {
                 r.borders.sort();
             } else {
@@ -102,16 +108,6 @@ fn get_regions(puzzle: &Puzzle) -> Vec<Region> {
     rs
 }
 
-fn get_offset(d: char) -> (i32, i32) {
-    match d {
-        '<' => (-1, 0),
-        '>' => (1, 0),
-        '^' => (0, -1),
-        'v' => (0, 1),
-        _ => panic!("invalid direction")
-    }
-}
-
 fn part_1(puzzle: &Puzzle) -> u32 {
     get_regions(puzzle).iter().map(|r| r.area * r.perimeter).sum()
 }
@@ -120,13 +116,6 @@ fn part_2(puzzle: &Puzzle) -> u32 {
     get_regions(puzzle).iter().map(|r| r.area * r.edges).sum()
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_12/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,32 +1,46 @@
+//! Day 13: Claw Contraption.
+
+use crate::aoc::{Answer, Solution, parse::{self, ParseError}};
+
 #[derive(Debug)]
 struct Game {
     button_a: (i64, i64),
     button_b: (i64, i64),
     prize: (i64, i64)
 }
-struct Puzzle {
+/// The claw machines.
+pub struct Puzzle {
     games: Vec<Game>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let data = data.trim().replace("\r", "");
         let parse_game = |s: &str| {
             let mut abp = s.lines();
-            let (a_x, a_y) = abp.next()?.trim().split_once(": ")?.1.split_once(", ")?;
-            let (b_x, b_y) = abp.next()?.trim().split_once(": ")?.1.split_once(", ")?;
-            let (p_x, p_y) = abp.next()?.trim().split_once(": ")?.1.split_once(", ")?;
-            let button_a = (a_x.trim_start_matches("X+").parse().ok()?, a_y.trim_start_matches("Y+").parse().ok()?);
-            let button_b = (b_x.trim_start_matches("X+").parse().ok()?, b_y.trim_start_matches("Y+").parse().ok()?);
-            let prize = (p_x.trim_start_matches("X=").parse().ok()?, p_y.trim_start_matches("Y=").parse().ok()?);
-            Some(Game {button_a, button_b, prize})
+            let mut parse_xy = |name: &str, (x_prefix, y_prefix): (&str, &str)| {
+                let line = parse::next(&data, s, &mut abp, name)?.trim();
+                let (x, y) = parse::split_once(&data, parse::split_once(&data, line, ": ")?.1, ", ")?;
+                let x = parse::value(&data, x.trim_start_matches(x_prefix), "a number")?;
+                let y = parse::value(&data, y.trim_start_matches(y_prefix), "a number")?;
+                Ok((x, y))
+            };
+            let button_a = parse_xy("button A", ("X+", "Y+"))?;
+            let button_b = parse_xy("button B", ("X+", "Y+"))?;
+            let prize = parse_xy("prize", ("X=", "Y="))?;
+            Ok(Game {button_a, button_b, prize})
         };
-        let games: Option<_> = data.trim().replace("\r", "").split("\n\n").map(parse_game).collect();
+        let games: Result<_, _> = data.split("\n\n").map(parse_game).collect();
         let games = games?;
-        Some(Puzzle {games})
+        Ok(Puzzle {games})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
 }
 
@@ -66,13 +80,6 @@ fn part_2(puzzle: &Puzzle) -> i64 {
     }).sum()
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_13/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,6 @@
-use crate::aoc::grid::Grid;
+//! Day 14: Restroom Redoubt.
+
+use crate::aoc::{Answer, Solution, diagnostic, grid::Grid, parse::{self, ParseError}};
 
 struct Robot {
     px: i32,
@@ -6,27 +8,32 @@ struct Robot {
     vx: i32,
     vy: i32
 }
-struct Puzzle {
+/// The robots positions and velocities.
+pub struct Puzzle {
     robots: Vec<Robot>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let parse_robot = |s: &str| {
-            let (p, v) = s.split_once(" ")?;
-            let (px, py) = p.trim_start_matches("p=").split_once(",")?;
-            let (vx, vy) = v.trim_start_matches("v=").split_once(",")?;
-            let (px, py) = (px.parse().ok()?, py.parse().ok()?);
-            let (vx, vy) = (vx.parse().ok()?, vy.parse().ok()?);
-            Some(Robot {px, py, vx, vy})
+            let (p, v) = parse::split_once(data, s, " ")?;
+            let (px, py) = parse::split_once(data, p.trim_start_matches("p="), ",")?;
+            let (vx, vy) = parse::split_once(data, v.trim_start_matches("v="), ",")?;
+            let (px, py) = (parse::value(data, px, "a position")?, parse::value(data, py, "a position")?);
+            let (vx, vy) = (parse::value(data, vx, "a velocity")?, parse::value(data, vy, "a velocity")?);
+            Ok(Robot {px, py, vx, vy})
         };
-        let robots: Option<_> = data.trim().lines().map(parse_robot).collect();
+        let robots: Result<_, _> = data.trim().lines().map(parse_robot).collect();
         let robots = robots?;
-        Some(Puzzle {robots})
+        Ok(Puzzle {robots})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self, (101, 103)))))
     }
 }
 
@@ -58,7 +65,7 @@ fn part_1(puzzle: &Puzzle) -> u32 {
     quadrants(puzzle, (101, 103), 100)
 }
 
-fn print_robots(robots: &Vec<Robot>, size: (usize, usize), time: u32) {
+fn print_robots(robots: &[Robot], size: (usize, usize), time: u32) {
     let (w, h) = size;
     let (w, h) = (w as i32, h as i32);
     let mut map = Grid::new(size, ' ');
@@ -70,11 +77,9 @@ fn print_robots(robots: &Vec<Robot>, size: (usize, usize), time: u32) {
     }) {
         map.set(p, '#');
     }
-    println!("{map}");
+    diagnostic!("after {time} seconds:\n{map}");
 }
 
-use std::collections::HashMap;
-
 fn part_2(puzzle: &Puzzle, size: (usize, usize)) -> u32 {
     let n = puzzle.robots.len();
     let (w, h) = size;
@@ -120,13 +125,6 @@ fn part_2(puzzle: &Puzzle, size: (usize, usize)) -> u32 {
     0
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_14/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle, (101, 103)));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,21 +1,29 @@
-use crate::aoc::grid::Grid;
+//! Day 15: Warehouse Woes.
 
-struct Puzzle {
+use crate::aoc::{Answer, Solution, grid::{Direction, Grid}, parse::ParseError};
+
+/// The warehouse map and the robot moves.
+pub struct Puzzle {
     map: Grid<char>,
-    moves: Vec<char>
+    moves: Vec<Direction>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let data = data.trim().replace("\r", "");
-        let (map, moves) = data.split_once("\n\n")?;
+        let (map, moves) = data.split_once("\n\n").ok_or_else(|| ParseError::after(&data, &data, "an empty line before the moves"))?;
         let map = Grid::parse(map, "")?;
-        let moves = moves.lines().map(|s| s.trim()).collect::<String>().chars().collect();
-        Some(Puzzle {map, moves})
+        let data = &data;
+        let moves = moves.lines().map(|s| s.trim()).flat_map(|s| s.char_indices().map(move |(i, c)| {
+            Direction::try_from(c).map_err(|_| ParseError::at(data, &s[i..(i + c.len_utf8())], "a move"))
+        }));
+        let moves: Result<_, _> = moves.collect();
+        let moves = moves?;
+        Ok(Puzzle {map, moves})
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 }
 
@@ -26,13 +34,7 @@ fn part_1(puzzle: &Puzzle) -> u32 {
     let (mut x, mut y) = (x as i32, y as i32);
 
     for m in &puzzle.moves {
-        let (dx, dy) = match m {
-            '<' => (-1, 0),
-            '>' => (1, 0),
-            '^' => (0, -1),
-            'v' => (0, 1),
-            _ => panic!("invalid move")
-        };
+        let (dx, dy) = (m.offset().x as i32, m.offset().y as i32);
         let (mut nx, mut ny) = (x + dx, y + dy);
         while map.get((nx as usize, ny as usize)) == 'O' {
             nx += dx;
@@ -64,17 +66,6 @@ fn part_1(puzzle: &Puzzle) -> u32 {
     total as u32
 }
 
-fn part_2(data: &Puzzle) -> u32 {
-    todo!("part 2");
-}
-
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_15/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", part_1(&puzzle));
-    println!("part 2: {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,127 +1,73 @@
-// use std::collections::VecDeque;
+//! Day 16: Reindeer Maze.
+
 use std::collections::HashSet;
 
-struct Data {
-    grid: Vec<Vec<char>>
+use crate::aoc::{Answer, Solution, grid::{Direction, Grid}, parse::ParseError, search};
+
+/// The reindeer maze.
+pub struct Data {
+    grid: Grid<char>
 }
 
-impl Data {
-    fn parse(data: &str) -> Self {
-        let grid = data.trim().lines().map(|s| {
-            s.trim().chars().collect()
-        }).collect();
-        Data {grid}
+impl Solution for Data {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse(data, "")?;
+        Ok(Data {grid})
     }
 
-    fn get(&self, p: &Position) -> char {
-        self.grid[p.y as usize][p.x as usize]
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
-    // fn get_mut(&mut self, x: i32, y: i32) -> &mut char {
-    //     &mut self.grid[y as usize][x as usize]
-    // }
-
-    fn size(&self) -> (i32, i32) {
-        (self.grid[0].len() as i32, self.grid.len() as i32)
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
+}
 
-    fn find(&self, c: char) -> Option<Position> {
-        let (w, h) = self.size();
-        for y in 1..(h - 1) {
-            for x in 1..(w - 1) {
-                let p = Position {x, y};
-                if self.get(&p) == c {
-                    return 
-                    Some(p);
-                }
-            }
-        }
-        None
+impl std::fmt::Display for Data {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.grid)
     }
 }
 
-fn get_next_position(p: &Position, d: char) -> Position {
-    let (dx, dy) = match d {
-        '<' => (-1, 0),
-        '>' => (1, 0),
-        '^' => (0, -1),
-        'v' => (0, 1),
-        _ => panic!("invalid move")
-    };
-    Position {x: p.x + dx, y: p.y + dy}
-}
+// the reindeer, where it stands and where it faces
+type Tile = ((usize, usize), Direction);
 
-impl std::fmt::Display for Data {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let mut nl = false;
-        for r in &self.grid {
-            if nl {write!(f, "\n")?;}
-            for c in r {
-                write!(f, "{}", c)?;
-            }
-            nl = true;
-        }
-        Ok(())
+// moving forward costs 1, turning a quarter in place costs 1000
+fn moves(data: &Data, (p, d): Tile) -> Vec<(Tile, u32)> {
+    let mut moves = vec![((p, d.turn_left()), 1000), ((p, d.turn_right()), 1000)];
+    if let Some(np) = data.grid.step(p, d.offset()).filter(|&np| data.grid.get(np) != '#') {
+        moves.push(((np, d), 1));
     }
+    moves
 }
 
-#[derive(Debug)]
-struct Position {
-    x: i32,
-    y: i32
+fn start(data: &Data) -> Tile {
+    (data.grid.find('S').expect("start"), Direction::East)
 }
-#[derive(Debug)]
-struct Tile {
-    p: Position,
-    d: char,
-    t: u32
+
+fn is_end(data: &Data, (p, _): Tile) -> bool {
+    data.grid.get(p) == 'E'
 }
 
 fn solve_maze(data: &Data) -> Option<u32> {
-    let mut ps = Vec::new();
-    let mut vs = HashSet::new();
-    ps.push(Tile {p: data.find('S').expect("start"), d: '>', t: 0});
-    while !ps.is_empty() {
-        let Tile {p, d, t} = ps.pop().unwrap();
-        if data.get(&p) == 'E' {
-            return Some(t);
-        }
-        vs.insert((p.x, p.y, d));
-        let np = get_next_position(&p, d);
-        if !vs.contains(&(np.x, np.y, d)) && data.get(&np) != '#' {
-            ps.push(Tile {p: np, d, t: t + 1});
-        }
-        let nds = if (d == '<') || (d == '>') {('^', 'v')} else {('<', '>')};
-        let np = get_next_position(&p, nds.0);
-        if !vs.contains(&(np.x, np.y, d)) && data.get(&np) != '#' {
-            ps.push(Tile {p: np, d: nds.0, t: t + 1001});
-        }
-        let np = get_next_position(&p, nds.1);
-        if !vs.contains(&(np.x, np.y, d)) && data.get(&np) != '#' {
-            ps.push(Tile {p: np, d: nds.1, t: t + 1001});
-        }
-        ps.sort_by(|a, b| b.t.cmp(&a.t));
-    }
-    None
+    let found = search::dijkstra([start(data)], |&t| moves(data, t), |&t| is_end(data, t))?;
+    Some(found.cost)
 }
 
-fn part_1(data: &Data) -> u32 {
-    solve_maze(data).unwrap()
+// tiles on any best path, whatever the reindeer faces there
+fn best_tiles(data: &Data) -> Option<usize> {
+    let paths = search::dijkstra_all([start(data)], |&t| moves(data, t), |&t| is_end(data, t))?;
+    let tiles: HashSet<_> = paths.states().into_iter().map(|(p, _)| p).collect();
+    Some(tiles.len())
 }
 
-fn part_2(data: &Data) -> u32 {
-    todo!("part 2");
+fn part_1(data: &Data) -> u32 {
+    solve_maze(data).unwrap()
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_16/test_1.txt");
-    let data = Data::parse(data);
-    println!("{data}");
-
-    let data = include_str!("../../data/day_16/input.txt");
-    let data = Data::parse(data);
-    println!("part 1: {}", part_1(&data));
-    println!("part 2: {}", part_2(&data));
+fn part_2(data: &Data) -> usize {
+    best_tiles(data).unwrap()
 }
 
 #[cfg(test)]
@@ -131,13 +77,13 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("../../data/day_16/test_1.txt");
-        let data = Data::parse(data);
-        let size = data.size();
+        let data = Data::load(data);
+        let size = data.grid.size();
         assert!((size.0 == 15) && (size.1 == 15));
 
         let data = include_str!("../../data/day_16/test_2.txt");
-        let data = Data::parse(data);
-        let size = data.size();
+        let data = Data::load(data);
+        let size = data.grid.size();
         assert!((size.0 == 17) && (size.1 == 17));
     }
 
@@ -148,7 +94,7 @@ mod tests {
         #SE#
         ####
         ";
-        let data = Data::parse(data);
+        let data = Data::load(data);
         assert_eq!(part_1(&data), 1);
 
         let data = "
@@ -156,7 +102,7 @@ mod tests {
         #S.E#
         #####
         ";
-        let data = Data::parse(data);
+        let data = Data::load(data);
         assert_eq!(part_1(&data), 2);
 
         let data = "
@@ -165,7 +111,7 @@ mod tests {
         #S#
         ###
         ";
-        let data = Data::parse(data);
+        let data = Data::load(data);
         assert_eq!(part_1(&data), 1001);
 
         let data = "
@@ -175,35 +121,35 @@ mod tests {
         #S#
         ###
         ";
-        let data = Data::parse(data);
+        let data = Data::load(data);
         assert_eq!(part_1(&data), 1002);
     }
 
     #[test]
     fn test_part_1_1() {
         let data = include_str!("../../data/day_16/test_1.txt");
-        let data = Data::parse(data);
+        let data = Data::load(data);
         assert_eq!(part_1(&data), 7036);
     }
 
     #[test]
     fn test_part_1_2() {
         let data = include_str!("../../data/day_16/test_2.txt");
-        let data = Data::parse(data);
+        let data = Data::load(data);
         assert_eq!(part_1(&data), 11048);
     }
 
     #[test]
     fn test_part_2_1() {
         let data = include_str!("../../data/day_16/test_1.txt");
-        let data = Data::parse(data);
+        let data = Data::load(data);
         assert_eq!(part_2(&data), 45);
     }
 
     #[test]
     fn test_part_2_2() {
         let data = include_str!("../../data/day_16/test_2.txt");
-        let data = Data::parse(data);
+        let data = Data::load(data);
         assert_eq!(part_2(&data), 64);
     }
 }
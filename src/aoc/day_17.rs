@@ -1,5 +1,13 @@
+//! Day 17: Chronospatial Computer.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use crate::aoc::{Answer, Solution, diagnostic, parse::{self, ParseError}};
+
+/// The 3-bit computer, its registers and program.
 #[derive(Clone, Debug)]
-struct Computer {
+pub struct Computer {
     ip: usize,
     a: u64,
     b: u64,
@@ -8,30 +16,39 @@ struct Computer {
     out: String
 }
 
-impl Computer {
-    fn new(a: u64, b: u64, c: u64, program: Vec<u8>) -> Self {
-        Self {a, b, c, program, ip: 0, out: String::new()}
+impl Solution for Computer {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let mut lines = data.trim().lines();
+        let mut register = |name: &str| {
+            let line = parse::next(data, data.trim(), &mut lines, &format!("register {name}"))?;
+            parse::value(data, line.trim().trim_start_matches(&format!("Register {name}: ")), "a register value")
+        };
+        let (a, b, c) = (register("A")?, register("B")?, register("C")?);
+        lines.next();
+        let program = parse::next(data, data.trim(), &mut lines, "a program")?;
+        let program: Result<Vec<_>, _> = program.trim().trim_start_matches("Program: ").split(",").map(|s| {
+            match parse::value(data, s, "a 3-bit number")? {
+                b @ 0..=7 => Ok(b),
+                _ => Err(ParseError::at(data, s, "a 3-bit number"))
+            }
+        }).collect();
+        Ok(Self::new(a, b, c, program?))
     }
 
-    fn parse(data: &str) -> Option<Self> {
-        let mut data = data.trim().lines();
-        let a = data.next()?.trim().trim_start_matches("Register A: ").parse().ok()?;
-        let b = data.next()?.trim().trim_start_matches("Register B: ").parse().ok()?;
-        let c = data.next()?.trim().trim_start_matches("Register C: ").parse().ok()?;
-        data.next();
-        let program: Vec<_> = data.next()?.trim().trim_start_matches("Program: ").split(",").map(|s| s.parse()).collect();
-        if program.iter().any(|b| b.is_err()) {
-            return None;
-        }
-        let program: Vec<_> = program.into_iter().map(|b| b.unwrap()).collect();
-        if program.iter().any(|b| *b > 7) {
-            return None;
-        }
-        Some(Self::new(a, b, c, program))
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        let output = part_1(self).map_err(|e| e.to_string())?;
+        Ok(Some(Box::new(output)))
+    }
+
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_2(self)?)))
     }
+}
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("vaalid input")
+impl Computer {
+    /// Computer with the given registers and program, see [`assemble`].
+    pub fn new(a: u64, b: u64, c: u64, program: Vec<u8>) -> Self {
+        Self {a, b, c, program, ip: 0, out: String::new()}
     }
 
     // fn reset(&mut self, a: u64, b: u64, c: u64) {
@@ -42,72 +59,323 @@ impl Computer {
     //     self.out = String::new();
     // }
 
-    fn combo(&self, op: u8) -> u64 {
-        let op = op as u64;
+    fn combo(&self, op: u8) -> Result<u64, Fault> {
         match op {
-            0..=3 => op,
-            4 => self.a,
-            5 => self.b,
-            6 => self.c,
-            _ => panic!("invalid combo")
+            0..=3 => Ok(op as u64),
+            4 => Ok(self.a),
+            5 => Ok(self.b),
+            6 => Ok(self.c),
+            _ => Err(Fault::InvalidCombo {ip: self.ip - 2, operand: op})
         }
     }
 
-    fn adv(&mut self, operand: u8) {
-        self.a >>= self.combo(operand);
+    fn adv(&mut self, operand: u8) -> Result<(), Fault> {
+        self.a = shr(self.a, self.combo(operand)?);
+        Ok(())
     }
 
-    fn bxl(&mut self, operand: u8) {
+    fn bxl(&mut self, operand: u8) -> Result<(), Fault> {
         self.b ^= operand as u64;
+        Ok(())
     }
 
-    fn bst(&mut self, operand: u8) {
-        self.b = self.combo(operand) & 7;
+    fn bst(&mut self, operand: u8) -> Result<(), Fault> {
+        self.b = self.combo(operand)? & 7;
+        Ok(())
     }
-    
-    fn jnz(&mut self, operand: u8) {
+
+    fn jnz(&mut self, operand: u8) -> Result<(), Fault> {
         if self.a != 0 {
             self.ip = operand as usize;
         }
+        Ok(())
     }
 
-    fn bxc(&mut self) {
+    fn bxc(&mut self) -> Result<(), Fault> {
         self.b ^= self.c;
+        Ok(())
     }
 
-    fn out(&mut self, operand: u8) {
+    fn out(&mut self, operand: u8) -> Result<(), Fault> {
+        let value = self.combo(operand)? & 7;
         if !self.out.is_empty() {
             self.out.push(',');
         }
-        self.out.push(((self.combo(operand) & 7) as u8 + b'0') as char);
+        self.out.push((value as u8 + b'0') as char);
+        Ok(())
+    }
+
+    fn bdv(&mut self, operand: u8) -> Result<(), Fault> {
+        self.b = shr(self.a, self.combo(operand)?);
+        Ok(())
+    }
+
+    fn cdv(&mut self, operand: u8) -> Result<(), Fault> {
+        self.c = shr(self.a, self.combo(operand)?);
+        Ok(())
+    }
+
+    fn registers(&self) -> [u64; 3] {
+        [self.a, self.b, self.c]
+    }
+
+    /// Runs the instruction at the instruction pointer, `None` when the
+    /// program has halted. On a fault the computer is left before the
+    /// faulting instruction.
+    pub fn step(&mut self) -> Result<Option<Trace>, Fault> {
+        let ip = self.ip;
+        if ip == self.program.len() {
+            return Ok(None);
+        }
+        let (opcode, operand) = match (self.program.get(ip), self.program.get(ip + 1)) {
+            (Some(&opcode), Some(&operand)) => (opcode, operand),
+            _ => return Err(Fault::OutOfProgram {ip})
+        };
+        let before = self.registers();
+        self.ip += 2;
+        let done = match opcode {
+            0 => self.adv(operand),
+            1 => self.bxl(operand),
+            2 => self.bst(operand),
+            3 => self.jnz(operand),
+            4 => self.bxc(),
+            5 => self.out(operand),
+            6 => self.bdv(operand),
+            7 => self.cdv(operand),
+            _ => Err(Fault::InvalidOpcode {ip, opcode})
+        };
+        if let Err(e) = done {
+            self.ip = ip;
+            return Err(e);
+        }
+        Ok(Some(Trace {ip, opcode, operand, before, after: self.registers()}))
+    }
+
+    /// Runs until the program halts or reaches a breakpoint, the instruction
+    /// at a breakpoint is not run, unless it is the first one, so that
+    /// debugging again goes on. Each instruction run is given to `trace`.
+    pub fn debug(&mut self, options: &DebugOptions, mut trace: impl FnMut(&Trace)) -> Result<Stop, Fault> {
+        let mut cycles = 0;
+        loop {
+            if (cycles > 0) && options.breakpoints.contains(&self.ip) {
+                return Ok(Stop::Breakpoint(self.ip));
+            }
+            if options.cycle_limit.is_some_and(|limit| cycles == limit) {
+                return Err(Fault::CycleLimit(cycles));
+            }
+            match self.step()? {
+                Some(step) => trace(&step),
+                None => return Ok(Stop::Halted)
+            }
+            cycles += 1;
+        }
+    }
+
+    /// Runs the program until it halts, gives the comma separated output.
+    pub fn run(&mut self) -> Result<&str, Fault> {
+        while self.step()?.is_some() {}
+        Ok(self.out.as_str())
     }
 
-    fn bdv(&mut self, operand: u8) {
-        self.b = self.a >> self.combo(operand);
+    /// The program, decoded to run it many times.
+    pub fn compile(&self) -> Compiled {
+        Compiled::new(&self.program)
     }
+}
+
+/// A program decoded once, to run it fast from many values of the
+/// registers, see [`Compiled::run`].
+#[derive(Clone, Debug)]
+pub struct Compiled {
+    // the instruction at each address, a jump may land on an odd one
+    ops: Vec<Op>
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Operand {
+    Literal(u64),
+    A,
+    B,
+    C
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Adv(Operand),
+    Bxl(u64),
+    Bst(Operand),
+    Jnz(usize),
+    Bxc,
+    Out(Operand),
+    Bdv(Operand),
+    Cdv(Operand),
+    // faults when run, as it does in the computer
+    Fault(Fault)
+}
+
+impl Op {
+    fn decode(ip: usize, opcode: u8, operand: Option<u8>) -> Self {
+        let Some(operand) = operand else {
+            return Self::Fault(Fault::OutOfProgram {ip});
+        };
+        let combo = match operand {
+            0..=3 => Ok(Operand::Literal(operand as u64)),
+            4 => Ok(Operand::A),
+            5 => Ok(Operand::B),
+            6 => Ok(Operand::C),
+            _ => Err(Fault::InvalidCombo {ip, operand})
+        };
+        let op = match opcode {
+            0 => combo.map(Self::Adv),
+            1 => Ok(Self::Bxl(operand as u64)),
+            2 => combo.map(Self::Bst),
+            3 => Ok(Self::Jnz(operand as usize)),
+            4 => Ok(Self::Bxc),
+            5 => combo.map(Self::Out),
+            6 => combo.map(Self::Bdv),
+            7 => combo.map(Self::Cdv),
+            _ => Err(Fault::InvalidOpcode {ip, opcode})
+        };
+        op.unwrap_or_else(Self::Fault)
+    }
+}
 
-    fn cdv(&mut self, operand: u8) {
-        self.c = self.a >> self.combo(operand);
+impl Compiled {
+    /// Decodes `program`.
+    pub fn new(program: &[u8]) -> Self {
+        let ops = (0..program.len()).map(|ip| Op::decode(ip, program[ip], program.get(ip + 1).copied())).collect();
+        Self {ops}
     }
-    
-    fn run(&mut self) -> &str {
-        while self.ip != self.program.len() {
-            let (opcode, operand) = (self.program[self.ip], self.program[self.ip + 1]);
-            self.ip += 2;
-            match opcode {
-                0 => self.adv(operand),
-                1 => self.bxl(operand),
-                2 => self.bst(operand),
-                3 => self.jnz(operand),
-                4 => self.bxc(),
-                5 => self.out(operand),
-                6 => self.bdv(operand),
-                7 => self.cdv(operand),
-                _ => panic!("invalid opcode")
+
+    /// Runs the program from the registers A, B and C until it halts, its
+    /// outputs replace the content of `out`.
+    pub fn run(&self, a: u64, b: u64, c: u64, out: &mut Vec<u8>) -> Result<(), Fault> {
+        let (mut a, mut b, mut c) = (a, b, c);
+        out.clear();
+        let mut ip = 0;
+        while let Some(op) = self.ops.get(ip) {
+            let combo = |x| match x {
+                Operand::Literal(n) => n,
+                Operand::A => a,
+                Operand::B => b,
+                Operand::C => c
+            };
+            ip += 2;
+            match *op {
+                Op::Adv(x) => a = shr(a, combo(x)),
+                Op::Bxl(n) => b ^= n,
+                Op::Bst(x) => b = combo(x) & 7,
+                Op::Jnz(target) => if a != 0 {
+                    ip = target;
+                },
+                Op::Bxc => b ^= c,
+                Op::Out(x) => out.push((combo(x) & 7) as u8),
+                Op::Bdv(x) => b = shr(a, combo(x)),
+                Op::Cdv(x) => c = shr(a, combo(x)),
+                Op::Fault(ref fault) => return Err(fault.clone())
             }
         }
+        if ip == self.ops.len() {
+            Ok(())
+        } else {
+            Err(Fault::OutOfProgram {ip})
+        }
+    }
+
+    /// Same as [`Compiled::run`], into a new vector.
+    pub fn outputs(&self, a: u64, b: u64, c: u64) -> Result<Vec<u8>, Fault> {
+        let mut out = Vec::new();
+        self.run(a, b, c, &mut out)?;
+        Ok(out)
+    }
+}
+
+// `a` divided by 2 to the power of `n`
+fn shr(a: u64, n: u64) -> u64 {
+    u32::try_from(n).ok().and_then(|n| a.checked_shr(n)).unwrap_or(0)
+}
+
+/// A fault stopping the computer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fault {
+    /// The combo operand 7 is reserved.
+    InvalidCombo {
+        /// Address of the instruction.
+        ip: usize,
+        /// The operand.
+        operand: u8
+    },
+    /// Not an opcode of the computer.
+    InvalidOpcode {
+        /// Address of the instruction.
+        ip: usize,
+        /// The opcode.
+        opcode: u8
+    },
+    /// The instruction pointer is not on a whole instruction nor right after the program.
+    OutOfProgram {
+        /// The instruction pointer.
+        ip: usize
+    },
+    /// The program ran that many instructions without halting, see [`DebugOptions::cycle_limit`].
+    CycleLimit(u64)
+}
+
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::InvalidCombo {ip, operand} => write!(f, "invalid combo operand {operand} at {ip:02}"),
+            Self::InvalidOpcode {ip, opcode} => write!(f, "invalid opcode {opcode} at {ip:02}"),
+            Self::OutOfProgram {ip} => write!(f, "instruction pointer {ip:02} out of the program"),
+            Self::CycleLimit(cycles) => write!(f, "still running after {cycles} instructions")
+        }
+    }
+}
+
+impl std::error::Error for Fault {}
+
+/// Why [`Computer::debug`] returned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stop {
+    /// The program halted.
+    Halted,
+    /// The instruction pointer reached a breakpoint.
+    Breakpoint(usize)
+}
+
+/// Breakpoints and limit of [`Computer::debug`].
+#[derive(Clone, Debug, Default)]
+pub struct DebugOptions {
+    /// Addresses to stop at.
+    pub breakpoints: Vec<usize>,
+    /// Number of instructions after which to give up.
+    pub cycle_limit: Option<u64>
+}
+
+/// An instruction run, with the registers A, B and C around it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trace {
+    /// Address of the instruction.
+    pub ip: usize,
+    /// The opcode.
+    pub opcode: u8,
+    /// The operand.
+    pub operand: u8,
+    /// The registers before.
+    pub before: [u64; 3],
+    /// The registers after.
+    pub after: [u64; 3]
+}
 
-        self.out.as_str()
+impl std::fmt::Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let [a, b, c] = self.before;
+        let [na, nb, nc] = self.after;
+        write!(
+            f,
+            "{:02}: {} {} ({})  A: {a} B: {b} C: {c} -> A: {na} B: {nb} C: {nc}",
+            self.ip, MNEMONICS[self.opcode as usize % 8], self.operand, instruction(self.opcode, self.operand, &BTreeSet::new())
+        )
     }
 }
 
@@ -117,82 +385,245 @@ impl std::fmt::Display for Computer {
         writeln!(f, "B: {:X}", self.b)?;
         writeln!(f, "C: {:X}", self.c)?;
         writeln!(f, "IP: {:X}", self.ip)?;
-        writeln!(f, "")?;
-        for ip in 0..(self.program.len() / 2) {
-            let (opcode, operand) = (self.program[ip * 2], self.program[ip * 2 + 1]);
-            let combo = if ((opcode != 1) && (opcode != 3) && (opcode != 4)) && (operand > 3) && (operand < 7) {
-                ["A", "B", "C"][(operand - 4) as usize]
-            } else {
-                ""
-            };
-            let opcode =  match opcode {
-                0 => "adv",
-                1 => "bxl",
-                2 => "bst",
-                3 => "jnz",
-                4 => "bxc",
-                5 => "out",
-                6 => "bdv",
-                7 => "cdv",
-                _ => panic!("invalid opcode")
-            };
-            write!(f, "{:02}: {} {}", ip * 2, opcode, operand)?;
-            if !combo.is_empty() {
-                write!(f, " ({})", combo)?;
-            }
-            writeln!(f, "")?;
-        }
-        writeln!(f, "")?;
+        writeln!(f)?;
+        writeln!(f, "{}", disassemble(&self.program))?;
         write!(f, "OUT: '{}'", self.out)?;
         Ok(())
     }
 }
 
-fn part_1() -> String {
-    let computer = include_str!("../../data/day_17/input.txt");
-    let mut computer = Computer::parse(computer).expect("valid input");
-    computer.run().to_string()
+const MNEMONICS: [&str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+fn combo_name(operand: u8) -> Option<String> {
+    match operand {
+        0..=3 => Some(operand.to_string()),
+        4..=6 => Some(["A", "B", "C"][(operand - 4) as usize].to_string()),
+        _ => None
+    }
 }
 
-fn run_loop(a: u64) -> u8 {
-    let b = (a & 7) ^ 4;
-    let c = a >> b;
-    (((b ^ c) ^ 4) & 7) as u8
+// symbolic form of an instruction, see `disassemble`
+fn instruction(opcode: u8, operand: u8, targets: &BTreeSet<u8>) -> String {
+    let combo = combo_name(operand);
+    let line = match opcode {
+        0 => combo.map(|x| format!("A >>= {x}")),
+        1 => Some(format!("B ^= {operand}")),
+        2 => combo.map(|x| format!("B = {x} % 8")),
+        3 if targets.contains(&operand) => Some(format!("jnz L{operand}")),
+        3 => Some(format!("jnz {operand}")),
+        4 if operand == 0 => Some("B ^= C".to_string()),
+        4 => Some(format!("B ^= C ({operand})")),
+        5 => combo.map(|x| format!("out {x} % 8")),
+        6 => combo.map(|x| format!("B = A >> {x}")),
+        7 => combo.map(|x| format!("C = A >> {x}")),
+        _ => None
+    };
+    let line = line.or_else(|| Some(format!("{} {operand}", MNEMONICS.get(opcode as usize)?)));
+    line.unwrap_or_else(|| format!(".byte {opcode}\n    .byte {operand}"))
 }
 
-fn solve_part_2(computer: &Computer) -> Option<u64> {
-    let mut ps = vec![0];
-    for b in computer.program.iter().rev() {
-        let mut nps = Vec::new();
-        for a in &ps {
-            let na = a << 3;
-            for na in na..(na + 8) {
-                if run_loop(na) == *b {
-                    if na > 0 {nps.push(na);}
-                }
+/// Listing of a program, one instruction per line with symbolic operands:
+///
+/// | opcode | listing      |
+/// |--------|--------------|
+/// | adv x  | `A >>= x`    |
+/// | bxl n  | `B ^= n`     |
+/// | bst x  | `B = x % 8`  |
+/// | jnz n  | `jnz Ln`     |
+/// | bxc n  | `B ^= C (n)` |
+/// | out x  | `out x % 8`  |
+/// | bdv x  | `B = A >> x` |
+/// | cdv x  | `C = A >> x` |
+///
+/// where `x` is a combo operand, `0` to `3`, `A`, `B` or `C`. Jump targets
+/// get a `Ln:` label line, the operand `bxc` ignores is left out when 0,
+/// instructions with the invalid combo operand 7 are listed as their mnemonic
+/// and operand, and a trailing odd byte as `.byte n`. See [`assemble`] for the way back.
+pub fn disassemble(program: &[u8]) -> String {
+    let targets: BTreeSet<_> = program.chunks(2)
+        .filter(|i| (i.len() == 2) && (i[0] == 3) && ((i[1] % 2) == 0) && ((i[1] as usize) < program.len()))
+        .map(|i| i[1])
+        .collect();
+    let mut lines = Vec::new();
+    for (ip, bytes) in program.chunks(2).enumerate() {
+        let (opcode, operand) = match *bytes {
+            [opcode, operand] => (opcode, operand),
+            _ => {
+                lines.push(format!("    .byte {}", bytes[0]));
+                break;
+            }
+        };
+        if targets.contains(&((ip * 2) as u8)) {
+            lines.push(format!("L{}:", ip * 2));
+        }
+        let line = instruction(opcode, operand, &targets);
+        lines.push(format!("    {line}"));
+    }
+    lines.join("\n")
+}
+
+/// Program of a listing in the format of [`disassemble`], labels can be any
+/// word followed by `:`, jumps can also go to a number, `#` starts a comment.
+pub fn assemble(source: &str) -> Result<Vec<u8>, ParseError> {
+    let lines: Vec<_> = source.lines().map(|s| s.split('#').next().unwrap().trim()).filter(|s| !s.is_empty()).collect();
+    let mut labels = std::collections::HashMap::new();
+    let mut ip = 0;
+    for line in &lines {
+        if let Some(label) = line.strip_suffix(':') {
+            if label.is_empty() || !label.chars().all(|c| c.is_alphanumeric() || (c == '_')) {
+                return Err(ParseError::at(source, line, "a label"));
             }
+            if labels.insert(label, ip).is_some() {
+                return Err(ParseError::at(source, line, "a new label"));
+            }
+        } else {
+            ip += if line.starts_with(".byte") {1} else {2};
+        }
+    }
+
+    let number = |s: &str| match parse::value(source, s, "a 3-bit number")? {
+        n @ 0..=7 => Ok(n),
+        _ => Err(ParseError::at(source, s, "a 3-bit number"))
+    };
+    let combo = |s: &str| match s {
+        "A" => Ok(4),
+        "B" => Ok(5),
+        "C" => Ok(6),
+        s => match parse::value(source, s, "a combo operand")? {
+            n @ 0..=3 => Ok(n),
+            _ => Err(ParseError::at(source, s, "a combo operand"))
         }
-        if nps.is_empty() {
-            return None;
+    };
+    let target = |s: &str| match labels.get(s) {
+        Some(&ip) if ip < 8 => Ok(ip as u8),
+        Some(_) => Err(ParseError::at(source, s, "a label within the first 4 instructions")),
+        None if s.starts_with(|c: char| c.is_ascii_digit()) => number(s),
+        None => Err(ParseError::at(source, s, "a known label"))
+    };
+    let mut program = Vec::new();
+    for line in lines.into_iter().filter(|s| !s.ends_with(':')) {
+        let tokens: Vec<_> = line.split_whitespace().collect();
+        let instruction = match tokens[..] {
+            [".byte", n] => {
+                program.push(number(n)?);
+                continue;
+            }
+            ["A", ">>=", x] => (0, combo(x)?),
+            ["B", "^=", "C"] => (4, 0),
+            ["B", "^=", "C", n] if n.starts_with('(') && n.ends_with(')') => (4, number(&n[1..(n.len() - 1)])?),
+            ["B", "^=", n] => (1, number(n)?),
+            ["B", "=", x, "%", "8"] => (2, combo(x)?),
+            ["B", "=", "A", ">>", x] => (6, combo(x)?),
+            ["C", "=", "A", ">>", x] => (7, combo(x)?),
+            ["out", x, "%", "8"] => (5, combo(x)?),
+            ["jnz", t] => (3, target(t)?),
+            [mnemonic, n] => match MNEMONICS.iter().position(|m| *m == mnemonic) {
+                Some(opcode) => (opcode as u8, number(n)?),
+                None => return Err(ParseError::at(source, line, "an instruction"))
+            },
+            _ => return Err(ParseError::at(source, line, "an instruction"))
+        };
+        program.extend([instruction.0, instruction.1]);
+    }
+    Ok(program)
+}
+
+/// Debugging of the program run by part 1, logged with the diagnostics,
+/// see [`set_debugging`].
+#[derive(Clone, Debug, Default)]
+pub struct Debugging {
+    /// Whether each instruction run is logged as a [`Trace`].
+    pub trace: bool,
+    /// Breakpoints, where the computer is logged, and limit of the whole run.
+    pub options: DebugOptions
+}
+
+static DEBUGGING: Mutex<Option<Debugging>> = Mutex::new(None);
+
+/// Runs the program of part 1 under `debugging`, or normally for `None`.
+pub fn set_debugging(debugging: Option<Debugging>) {
+    *DEBUGGING.lock().unwrap() = debugging;
+}
+
+// runs to the end through the breakpoints, the cycle limit is for the whole run
+fn debug_run(computer: &mut Computer, debugging: &Debugging) -> Result<(), Fault> {
+    let mut options = debugging.options.clone();
+    let mut cycles = 0;
+    loop {
+        options.cycle_limit = debugging.options.cycle_limit.map(|limit| limit - cycles);
+        let stop = computer.debug(&options, |trace| {
+            cycles += 1;
+            if debugging.trace {
+                diagnostic!("{trace}");
+            }
+        });
+        match stop {
+            Ok(Stop::Halted) => return Ok(()),
+            Ok(Stop::Breakpoint(ip)) => diagnostic!("breakpoint at {ip:02}\n{computer}"),
+            Err(Fault::CycleLimit(_)) => return Err(Fault::CycleLimit(cycles)),
+            Err(e) => return Err(e)
         }
-        ps = nps;
     }
-    if ps.is_empty() {
-        None
-    } else {
-        Some(ps[0])
+}
+
+fn part_1(computer: &Computer) -> Result<String, Fault> {
+    let mut computer = computer.clone();
+    let debugging = DEBUGGING.lock().unwrap().clone();
+    match debugging {
+        Some(debugging) => debug_run(&mut computer, &debugging)?,
+        None => {
+            computer.run()?;
+        }
     }
+    Ok(computer.out)
 }
 
-fn part_2() -> u64 {
-    let computer = include_str!("../../data/day_17/input.txt");
-    let computer = Computer::load(computer);
-    solve_part_2(&computer).expect("solvable puzzle")
+// the search needs a single loop over A, A shifted right by 3 and one value
+// output per turn, so that each output only depends on the top bits of A,
+// and 3 bits of A per output must fit in 64 bits
+fn check_shape(program: &[u8]) -> Result<(), String> {
+    if program.len() > 21 {
+        return Err(format!("the program has {} values, A can only output 21 of them", program.len()));
+    }
+    let instructions: Vec<_> = program.chunks(2).collect();
+    let count = |opcode: u8| instructions.iter().filter(|i| i[0] == opcode).count();
+    if instructions.last() != Some(&&[3, 0][..]) || (count(3) != 1) {
+        return Err("the program must end with its only jump, back to 0".to_string());
+    }
+    if !instructions.contains(&&[0, 3][..]) || (count(0) != 1) {
+        return Err("the program must shift A right by 3 once per loop, and only then".to_string());
+    }
+    if count(5) != 1 {
+        return Err("the program must output once per loop".to_string());
+    }
+    Ok(())
 }
 
-pub(crate) fn solve() {
-    println!("part 1: {}", part_1());
-    println!("part 2: {}", part_2());
+// smallest A for the program to output itself, found from the last output
+// back, 3 bits of A for each
+fn solve_part_2(computer: &Computer) -> Result<u64, String> {
+    check_shape(&computer.program)?;
+    let compiled = computer.compile();
+    let mut out = Vec::new();
+    let program = &computer.program;
+    let mut candidates = vec![0];
+    for n in 1..=program.len() {
+        let expected = &program[(program.len() - n)..];
+        let mut next = Vec::new();
+        for a in candidates {
+            for a in ((a << 3)..((a << 3) + 8)).filter(|a| *a > 0) {
+                compiled.run(a, computer.b, computer.c, &mut out).map_err(|e| e.to_string())?;
+                if out == expected {
+                    next.push(a);
+                }
+            }
+        }
+        if next.is_empty() {
+            return Err(format!("no value of A outputs the program from position {}", program.len() - n));
+        }
+        candidates = next;
+    }
+    Ok(candidates[0])
 }
 
 #[cfg(test)]
@@ -212,42 +643,237 @@ mod tests {
     #[test]
     fn test_run() {
         let mut computer = Computer::new(0, 0, 0, vec![5, 0]);
-        assert_eq!(computer.run(), "0");
+        assert_eq!(computer.run(), Ok("0"));
 
         let mut computer = Computer::new(10, 0, 0, vec![5, 4]);
-        assert_eq!(computer.run(), "2");
+        assert_eq!(computer.run(), Ok("2"));
 
         let mut computer = Computer::new(0, 0, 9, vec![2, 6]);
-        assert_eq!(computer.run(), "");
+        assert_eq!(computer.run(), Ok(""));
         assert_eq!(computer.b, 1);
 
         let mut computer = Computer::new(0, 29, 0, vec![1, 7]);
-        assert_eq!(computer.run(), "");
+        assert_eq!(computer.run(), Ok(""));
         assert_eq!(computer.b, 26);
 
         let mut computer = Computer::new(0, 2024, 43690, vec![4, 0]);
-        assert_eq!(computer.run(), "");
+        assert_eq!(computer.run(), Ok(""));
         assert_eq!(computer.b, 44354);
 
         let mut computer = Computer::new(10, 0, 0, vec![5,0,5,1,5,4]);
-        assert_eq!(computer.run(), "0,1,2");
+        assert_eq!(computer.run(), Ok("0,1,2"));
 
         let mut computer = Computer::new(2024, 0, 0, vec![0,1,5,4,3,0]);
-        assert_eq!(computer.run(), "4,2,5,6,7,7,7,7,3,1,0");
+        assert_eq!(computer.run(), Ok("4,2,5,6,7,7,7,7,3,1,0"));
         assert_eq!(computer.a, 0);
 
         let computer = include_str!("../../data/day_17/test_1.txt");
         let mut computer = Computer::parse(computer).unwrap();
-        assert_eq!(computer.run(), "4,6,3,5,6,3,5,2,1,0");
+        assert_eq!(computer.run(), Ok("4,6,3,5,6,3,5,2,1,0"));
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let computer = include_str!("../../data/day_17/input.txt");
+        let computer = Computer::load(computer);
+        let listing = disassemble(&computer.program);
+        assert_eq!(listing.lines().collect::<Vec<_>>(), [
+            "L0:",
+            "    B = A % 8",
+            "    B ^= 4",
+            "    C = A >> B",
+            "    B ^= C (1)",
+            "    B ^= 4",
+            "    out B % 8",
+            "    A >>= 3",
+            "    jnz L0"
+        ]);
+        assert_eq!(assemble(&listing), Ok(computer.program));
+
+        assert_eq!(disassemble(&[0, 7, 4, 1, 3, 3, 5]), "    adv 7\n    B ^= C (1)\n    jnz 3\n    .byte 5");
+    }
+
+    #[test]
+    fn test_assemble() {
+        let source = "
+        # counts down from A, printing each step
+        start:
+            out A % 8
+            A >>= 1
+            jnz start
+        ";
+        let program = assemble(source).unwrap();
+        assert_eq!(program, [5, 4, 0, 1, 3, 0]);
+        let mut computer = Computer::new(5, 0, 0, program);
+        assert_eq!(computer.run(), Ok("5,2,1"));
+
+        // every instruction goes back and forth
+        for opcode in 0..8 {
+            for operand in 0..8 {
+                let program = vec![opcode, operand, 3, 0];
+                assert_eq!(assemble(&disassemble(&program)), Ok(program));
+            }
+        }
+
+        let e = assemble("x:\n  out A % 8\n  jnz y").unwrap_err();
+        assert_eq!((e.line, e.column, e.expected.as_str()), (3, 7, "a known label"));
+        let e = assemble("  B = A % 9").unwrap_err();
+        assert_eq!((e.line, e.column, e.expected.as_str()), (1, 3, "an instruction"));
+        let e = assemble("A >>= 7").unwrap_err();
+        assert_eq!((e.line, e.column, e.expected.as_str()), (1, 7, "a combo operand"));
+    }
+
+    #[test]
+    fn test_debug() {
+        let program = assemble("
+            loop:
+                out A % 8
+                A >>= 1
+                jnz loop
+        ").unwrap();
+        let mut computer = Computer::new(6, 0, 0, program);
+        let trace = computer.step().unwrap().unwrap();
+        assert_eq!(trace, Trace {ip: 0, opcode: 5, operand: 4, before: [6, 0, 0], after: [6, 0, 0]});
+        let trace = computer.step().unwrap().unwrap();
+        assert_eq!(trace.to_string(), "02: adv 1 (A >>= 1)  A: 6 B: 0 C: 0 -> A: 3 B: 0 C: 0");
+
+        let options = DebugOptions {breakpoints: vec![0], cycle_limit: None};
+        let mut traces = Vec::new();
+        assert_eq!(computer.debug(&options, |t| traces.push(t.ip)), Ok(Stop::Breakpoint(0)));
+        assert_eq!(traces, [4]);
+        assert_eq!(computer.debug(&options, |_| {}), Ok(Stop::Breakpoint(0)));
+        assert_eq!((computer.a, computer.out.as_str()), (1, "6,3"));
+        assert_eq!(computer.debug(&DebugOptions::default(), |_| {}), Ok(Stop::Halted));
+        assert_eq!(computer.out, "6,3,1");
+        assert_eq!(computer.step(), Ok(None));
+
+        // never halts, A is never shifted
+        let mut computer = Computer::new(1, 0, 0, assemble("l:\n out A % 8\n jnz l").unwrap());
+        let options = DebugOptions {breakpoints: Vec::new(), cycle_limit: Some(100)};
+        assert_eq!(computer.debug(&options, |_| {}), Err(Fault::CycleLimit(100)));
+        assert_eq!(computer.out.len(), 99);
+
+        // the limit holds across breakpoints
+        let mut computer = Computer::new(1, 0, 0, assemble("l:\n out A % 8\n jnz l").unwrap());
+        let debugging = Debugging {trace: false, options: DebugOptions {breakpoints: vec![2], cycle_limit: Some(9)}};
+        assert_eq!(debug_run(&mut computer, &debugging), Err(Fault::CycleLimit(9)));
+        assert_eq!(computer.out, "1,1,1,1,1");
+    }
+
+    #[test]
+    fn test_faults() {
+        let mut computer = Computer::new(1, 0, 0, vec![5, 4, 0, 7]);
+        assert_eq!(computer.run(), Err(Fault::InvalidCombo {ip: 2, operand: 7}));
+        assert_eq!((computer.ip, computer.out.as_str()), (2, "1"));
+        assert_eq!(computer.run().unwrap_err().to_string(), "invalid combo operand 7 at 02");
+
+        let mut computer = Computer::new(1, 0, 0, vec![3, 5, 5, 4]);
+        assert_eq!(computer.run(), Err(Fault::OutOfProgram {ip: 5}));
+        let mut computer = Computer::new(1, 0, 0, vec![9, 0]);
+        assert_eq!(computer.run(), Err(Fault::InvalidOpcode {ip: 0, opcode: 9}));
+
+        let mut computer = Computer::new(u64::MAX, 0, 100, vec![0, 6]);
+        assert_eq!(computer.run(), Ok(""));
+        assert_eq!(computer.a, 0);
+
+        // given as errors by the solution, not panics
+        let computer = Computer::load("Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 7,7\n");
+        assert_eq!(computer.part_1().err(), Some("invalid combo operand 7 at 00".to_string()));
+        assert!(computer.part_2().err().unwrap().contains("jump"));
+    }
+
+    #[test]
+    fn test_compiled() {
+        // the same outputs and faults as the computer
+        let same = |a, b, c, program: Vec<u8>| {
+            let compiled = Compiled::new(&program);
+            let mut computer = Computer::new(a, b, c, program);
+            let out = computer.run().map(|out| out.split(',').filter(|s| !s.is_empty()).map(|s| s.parse().unwrap()).collect());
+            assert_eq!(compiled.outputs(a, b, c), out);
+        };
+        let computer = Computer::load(include_str!("../../data/day_17/input.txt"));
+        for a in (0..2000).chain([u64::MAX, 156985331222018]) {
+            same(a, computer.b, computer.c, computer.program.clone());
+        }
+        same(729, 0, 0, vec![0, 1, 5, 4, 3, 0]);
+        same(2024, 0, 0, vec![0, 3, 5, 4, 3, 0]);
+        same(1, 0, 0, vec![5, 4, 0, 7]);
+        same(1, 0, 0, vec![3, 5, 5, 4]);
+        same(1, 0, 0, vec![3, 1, 5, 4]);
+        same(1, 0, 0, vec![9, 0]);
+        same(1, 0, 0, vec![5, 4, 0]);
+        same(u64::MAX, 0, 100, vec![0, 6]);
+
+        let compiled = computer.compile();
+        let mut out = vec![9];
+        assert_eq!(compiled.run(156985331222018, 0, 0, &mut out), Ok(()));
+        assert_eq!(out, computer.program);
+    }
+
+    #[test]
+    #[ignore = "timing, run with cargo test --release -- --ignored --nocapture"]
+    fn bench_compiled() {
+        use std::time::Instant;
+
+        // the same values of A as part 2 tries, a few loops each
+        let computer = Computer::load(include_str!("../../data/day_17/input.txt"));
+        let values = (0..1_000_000u64).map(|a| a << 20);
+        let start = Instant::now();
+        let mut outputs = 0;
+        for a in values.clone() {
+            let mut computer = Computer::new(a, computer.b, computer.c, computer.program.clone());
+            outputs += computer.run().unwrap().split(',').count();
+        }
+        let interpreted = start.elapsed();
+
+        let start = Instant::now();
+        let compiled = computer.compile();
+        let mut out = Vec::new();
+        let mut compiled_outputs = 0;
+        for a in values {
+            compiled.run(a, computer.b, computer.c, &mut out).unwrap();
+            compiled_outputs += out.len();
+        }
+        let compiled = start.elapsed();
+
+        println!("Computer::run: {interpreted:?}, Compiled::run: {compiled:?}, {:.1} times faster", interpreted.as_secs_f64() / compiled.as_secs_f64());
+        assert_eq!(compiled_outputs, outputs);
     }
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(), "7,0,7,3,4,1,3,0,1");
+        let computer = include_str!("../../data/day_17/input.txt");
+        let computer = Computer::load(computer);
+        assert_eq!(part_1(&computer), Ok("7,0,7,3,4,1,3,0,1".to_string()));
+    }
+
+    #[test]
+    fn test_solve_part_2() {
+        let computer = include_str!("../../data/day_17/test_2.txt");
+        let computer = Computer::load(computer);
+        assert_eq!(solve_part_2(&computer), Ok(117440));
+
+        let computer = include_str!("../../data/day_17/test_1.txt");
+        let computer = Computer::load(computer);
+        assert_eq!(solve_part_2(&computer), Err("the program must shift A right by 3 once per loop, and only then".to_string()));
+
+        let computer = Computer::new(0, 0, 0, vec![0, 3, 5, 4, 3, 2]);
+        assert!(solve_part_2(&computer).unwrap_err().contains("jump"));
+
+        // the right shape padded with `B ^= 0` to 22 values, which need 66 bits of A
+        let program = [vec![0, 3, 5, 4], [1, 0].repeat(8), vec![3, 0]].concat();
+        let computer = Computer::new(0, 0, 0, program);
+        assert_eq!(solve_part_2(&computer), Err("the program has 22 values, A can only output 21 of them".to_string()));
+
+        // outputs a constant, never itself
+        let computer = Computer::new(0, 0, 0, vec![0, 3, 5, 1, 3, 0]);
+        assert_eq!(solve_part_2(&computer), Err("no value of A outputs the program from position 5".to_string()));
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(), 156985331222018);
+        let computer = include_str!("../../data/day_17/input.txt");
+        let computer = Computer::load(computer);
+        assert_eq!(solve_part_2(&computer), Ok(156985331222018));
     }
 }
@@ -1,18 +1,31 @@
-use std::collections::{HashMap, VecDeque};
+//! Day 19: Linen Layout.
 
+use std::collections::HashMap;
+
+use crate::aoc::{Answer, Solution, parse::{self, ParseError}};
+
+/// The towel patterns and the designs to make.
 #[derive(Debug)]
-struct Data {
+pub struct Data {
     patterns: Vec<String>,
     designs: Vec<String>
 }
 
-impl Data {
-    fn parse(data: &str) -> Option<Self> {
-        let mut data = data.trim().lines();
-        let patterns = data.next()?.split(", ").map(String::from).collect();
-        data.next();
-        let designs = data.map(String::from).collect();
-        Some(Data {patterns, designs})
+impl Solution for Data {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let mut lines = data.trim().lines();
+        let patterns = parse::next(data, data.trim(), &mut lines, "towel patterns")?.split(", ").map(String::from).collect();
+        lines.next();
+        let designs = lines.map(String::from).collect();
+        Ok(Data {patterns, designs})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
+    }
+
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
 }
 
@@ -43,14 +56,6 @@ fn part_2(data: &Data) -> usize {
     data.designs.iter().map(|d| count(d, &data.patterns)).sum()
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_19/input.txt");
-    let data = Data::parse(data).expect("bad input");
-
-    println!("part 1: {}", part_1(&data));
-    println!("part 2: {}", part_2(&data));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
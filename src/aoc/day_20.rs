@@ -1,72 +1,46 @@
-use std::collections::HashMap;
+//! Day 20: Race Condition.
 
-use crate::aoc::{grid::Grid, maze::Maze};
+use std::collections::BTreeMap;
 
-struct Puzzle {
+use crate::aoc::{Answer, Solution, maze::{CheatMetric, CheatRules, Maze}, parse::ParseError};
+
+/// The racetrack.
+pub struct Puzzle {
     maze: Maze
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
-        Some(Self {maze: Maze::parse(data)?})
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        Ok(Self {maze: Maze::parse(data)?})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_2(self))))
     }
 }
 
-fn get_cheats(maze: &Maze, cheat: usize, save: usize) -> Option<Vec<(usize, ((usize, usize), (usize, usize)))>> {
+// number of cheats by time saved
+fn get_cheats(maze: &Maze, cheat: usize) -> Option<BTreeMap<usize, usize>> {
     let map = maze.get_map();
-    let begin = map.find('S')?;
-    let end = map.find('E')?;
-
-    let mut distances = Grid::new(maze.get_map().size(), usize::MAX);
-    for (p, _, d) in maze.explore(end, '#') {
-        distances.set(p, d);
-    }
-    let distance = distances.get(begin);
+    let (start, end) = (map.find('S')?, map.find('E')?);
+    maze.cheats(&CheatRules {start, end, walls: '#', radius: cheat, metric: CheatMetric::Manhattan})
+}
 
-    let mut cheats = HashMap::new();
-    for (d, p) in maze.get_path(begin, end, '#')?.enumerate() {
-        for (cp, _, cd) in map.explore(p, |_, _, _| true).skip(1) {
-            if cd > cheat {break;}
-            if (cd == 1) && map.get(cp) != '#' {continue;}
-            if !cheats.contains_key(&(p, cp)) {
-                let de = distances.get(cp);
-                if de != usize::MAX {
-                    let d = d + cd + de;
-                    if distance > d {
-                        cheats.insert((p, cp), distance - d);
-                    }
-                }
-            }
-        }
-    }
-    let mut cheats: Vec<_> = cheats.into_iter()
-        .filter(|(_, s)| *s >= save)
-        .map(|(pcp, s)| (s, pcp))
-        .collect();
-    cheats.sort();
-    Some(cheats)
+fn count_cheats(puzzle: &Puzzle, cheat: usize, save: usize) -> usize {
+    let cheats = get_cheats(&puzzle.maze, cheat).expect("solvable puzzle");
+    cheats.range(save..).map(|(_, n)| n).sum()
 }
 
 fn solve_part_1(puzzle: &Puzzle) -> usize {
-    let cheats = get_cheats(&puzzle.maze, 2, 100).expect("solvable puzzle");
-    cheats.len()
+    count_cheats(puzzle, 2, 100)
 }
 
 fn solve_part_2(puzzle: &Puzzle) -> usize {
-    let cheats = get_cheats(&puzzle.maze, 20, 100).expect("solvable puzzle");
-    cheats.len()
-
-}
-
-pub(crate) fn solve() {
-    let puzzle = include_str!("../../data/day_20/input.txt");
-    let puzzle = Puzzle::load(puzzle);
-    println!("part 1: {}", solve_part_1(&puzzle));
-    println!("part 2: {:?}", solve_part_2(&puzzle));
+    count_cheats(puzzle, 20, 100)
 }
 
 #[cfg(test)]
@@ -79,15 +53,8 @@ mod tests {
         let puzzle = Puzzle::load(puzzle);
         let maze = &puzzle.maze;
 
-        let cheats = get_cheats(maze, 2, 1).unwrap();
-        let mut save = HashMap::new();
-        for (s, _) in cheats {
-            let ts = save.entry(s).or_insert(0);
-            *ts += 1;
-        }
-        let mut save: Vec<_> = save.into_iter().map(|(s, t)| (s, t)).collect();
-        save.sort();
-        let mut i = save.into_iter();
+        let cheats = get_cheats(maze, 2).unwrap();
+        let mut i = cheats.into_iter();
         assert_eq!(i.next(), Some((2, 14)));
         assert_eq!(i.next(), Some((4, 14)));
         assert_eq!(i.next(), Some((6, 2)));
@@ -101,15 +68,8 @@ mod tests {
         assert_eq!(i.next(), Some((64, 1)));
         assert_eq!(i.next(), None);
 
-        let cheats = get_cheats(maze, 20, 50).unwrap();
-        let mut save = HashMap::new();
-        for (s, _) in cheats {
-            let ts = save.entry(s).or_insert(0);
-            *ts += 1;
-        }
-        let mut save: Vec<_> = save.into_iter().map(|(s, t)| (s, t)).collect();
-        save.sort();
-        let mut i = save.into_iter();
+        let cheats = get_cheats(maze, 20).unwrap();
+        let mut i = cheats.range(50..).map(|(s, n)| (*s, *n));
         assert_eq!(i.next(), Some((50, 32)));
         assert_eq!(i.next(), Some((52, 31)));
         assert_eq!(i.next(), Some((54, 29)));
@@ -1,4 +1,8 @@
-use crate::aoc::{grid::Grid, maze::Maze};
+//! Day 21: Keypad Conundrum.
+
+use std::collections::HashMap;
+
+use crate::aoc::{Answer, Solution, diagnostic, grid::Direction, is_verbose, maze::Maze, parse::{self, ParseError}};
 
 /*
 numeric keypad
@@ -35,105 +39,298 @@ _^A
 <v>
 ";
 
-const CODES: &str = "
-341A
-083A
-802A
-973A
-780A
-";
+/// The codes to type on the door.
+pub struct Puzzle {
+    codes: Vec<String>
+}
 
-/*
-type Keypad = Grid<char>;
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let mut codes = Vec::new();
+        for line in data.trim().lines().map(|s| s.trim()) {
+            if let Some(i) = line.find(|c: char| !c.is_ascii_digit() && (c != 'A')) {
+                return Err(ParseError::at(data, &line[i..], "a key of the numeric keypad"));
+            }
+            parse::value::<u64>(data, line.trim_end_matches('A'), "a numeric code")?;
+            codes.push(line.to_string());
+        }
+        Ok(Puzzle {codes})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
+    }
 
-#[derive(Debug, Clone)]
-struct Robot {
-    pad: Keypad,
-    position: (usize, usize)
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
+    }
+}
+
+type Keypad = Maze;
+
+// the gap of the keypads, no arm may go over it
+const GAP: char = '_';
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Pad {
+    Numeric,
+    Directional
 }
 
-struct Puzzle {
-    robots: [Robot; 4]
+// arrows moving an arm from key `a` to key `b`, one for each shortest way
+fn get_dirs(pad: &Keypad, a: char, b: char) -> Vec<String> {
+    let map = pad.get_map();
+    let (begin, end) = (map.find(a).expect("key"), map.find(b).expect("key"));
+    let paths = pad.get_paths(begin, end, GAP).expect("reachable key");
+    paths.iter().map(|path| path.windows(2).map(|w| {
+        let d = Direction::ALL.into_iter().find(|d| map.step(w[0], d.offset()) == Some(w[1]));
+        char::from(d.expect("adjacent keys"))
+    }).collect()).collect()
 }
 
-impl Puzzle {
-    fn load() -> Self {
-        let robots = [
-            NUMERIC_KEYPAD,
-            DIRECTIONAL_KEYPAD,
-            DIRECTIONAL_KEYPAD,
-            DIRECTIONAL_KEYPAD
-        ];
-        let robots: Vec<_> = robots.into_iter().map(|d| {
-            let pad = Keypad::load(d, "");
-            let position = pad.find('A').unwrap();
-            Robot {pad, position}
-        }).collect();
-        let robots = <[Robot; 4]>::try_from(robots).unwrap();
-        Self {robots}
+// keys pressed by an arm over `pad` when typing `keys` on the keypad
+// controlling it, or the index of the key that sent it over the gap
+fn replay(pad: &Keypad, keys: &str) -> Result<String, usize> {
+    let map = pad.get_map();
+    let mut arm = map.find('A').expect("key");
+    let mut pressed = String::new();
+    for (i, key) in keys.chars().enumerate() {
+        match Direction::try_from(key) {
+            Ok(d) => {
+                arm = map.step(arm, d.offset()).filter(|&p| map.get(p) != GAP).ok_or(i)?;
+            }
+            Err('A') => pressed.push(map.get(arm)),
+            Err(_) => return Err(i)
+        }
     }
+    Ok(pressed)
 }
-*/
 
-struct Puzzle;
+/// A robot arm that went over a gap, or got a key it does not know, while
+/// replaying a sequence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Panic {
+    /// The robot, robot 1 types on the door.
+    pub robot: usize,
+    /// Index of the key typed for the robot.
+    pub key: usize
+}
 
-type Keypad = Maze;
+impl std::fmt::Display for Panic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "robot {} panicked at key {}", self.robot, self.key + 1)
+    }
+}
 
-fn get_dirs(pad: &Keypad, begin: (usize, usize), end: (usize, usize)) -> Option<String> {
-    let path: Vec<_> = pad.get_path(begin, end, '_')?.collect();
-    println!("begin: {:?}, end: {:?}", begin, end);
-    println!("path: {:?}", path);
-    let mut dirs = String::new();
-    for w in path.windows(2) {
-        match w {
-            &[p1, p2] => {
-                let (x1, y1) = (p1.0 as i32, p1.1 as i32);
-                let (x2, y2) = (p2.0 as i32, p2.1 as i32);
-                let d = ((x2 - x1), (y2 - y1));
-                match d {
-                    (-1, 0) => dirs.push('<'),
-                    (1, 0)  => dirs.push('>'),
-                    (0, -1) => dirs.push('^'),
-                    (0, 1)  => dirs.push('v'),
-                    _ => panic!("invalid dir {:?}", d)
-                }
-            },
-            _ => return None
+/// The keypads and the robots between the door and the one typing.
+pub struct Conductor {
+    numeric: Keypad,
+    directional: Keypad,
+    robots: usize,
+    // presses for the arm over a pad to go from a key to another and press it,
+    // by the number of directional keypads typed on above the pad
+    memo: HashMap<(Pad, char, char, usize), u64>
+}
+
+impl Conductor {
+    /// Conductor of `robots` robots on directional keypads, plus the one on
+    /// the door.
+    pub fn new(robots: usize) -> Self {
+        Self {
+            numeric: Keypad::load(NUMERIC_KEYPAD),
+            directional: Keypad::load(DIRECTIONAL_KEYPAD),
+            robots,
+            memo: HashMap::new()
+        }
+    }
+
+    /// Fewest presses on the last keypad to type `code` on the door.
+    pub fn presses(&mut self, code: &str) -> u64 {
+        self.presses_on(Pad::Numeric, code, self.robots + 1)
+    }
+
+    // fewest presses to type `keys` on `pad` with `depth` directional keypads
+    // above it, every arm starts and ends on `A`
+    fn presses_on(&mut self, pad: Pad, keys: &str, depth: usize) -> u64 {
+        if depth == 0 {
+            return keys.len() as u64;
+        }
+        let mut a = 'A';
+        let mut total = 0;
+        for b in keys.chars() {
+            total += self.press(pad, a, b, depth);
+            a = b;
+        }
+        total
+    }
+
+    fn keypad(&self, pad: Pad) -> &Keypad {
+        match pad {
+            Pad::Numeric => &self.numeric,
+            Pad::Directional => &self.directional
         }
     }
-    Some(dirs)
+
+    // the arrows and the press for the arm over `pad` to press `b` after `a`
+    fn ways(&self, pad: Pad, a: char, b: char) -> Vec<String> {
+        get_dirs(self.keypad(pad), a, b).into_iter().map(|mut dirs| {
+            dirs.push('A');
+            dirs
+        }).collect()
+    }
+
+    fn press(&mut self, pad: Pad, a: char, b: char, depth: usize) -> u64 {
+        if let Some(&n) = self.memo.get(&(pad, a, b, depth)) {
+            return n;
+        }
+        let n = self.ways(pad, a, b).into_iter().map(|dirs| {
+            self.presses_on(Pad::Directional, &dirs, depth - 1)
+        }).min().expect("a way");
+        self.memo.insert((pad, a, b, depth), n);
+        n
+    }
+
+    /// One of the shortest ways to type `code`, the keys typed by each robot
+    /// from the door, then the keys you type, each about 2.5 times longer.
+    pub fn sequences(&mut self, code: &str) -> Vec<String> {
+        let mut sequences = vec![code.to_string()];
+        let mut pad = Pad::Numeric;
+        for depth in (1..=(self.robots + 1)).rev() {
+            let keys = sequences.last().unwrap();
+            let mut typed = String::new();
+            let mut a = 'A';
+            for b in keys.chars() {
+                let ways = self.ways(pad, a, b);
+                let best = ways.into_iter().min_by_key(|dirs| self.presses_on(Pad::Directional, dirs, depth - 1));
+                typed.push_str(&best.expect("a way"));
+                a = b;
+            }
+            sequences.push(typed);
+            pad = Pad::Directional;
+        }
+        sequences
+    }
+
+    /// Code typed on the door when you type `keys`, or where an arm panicked.
+    pub fn simulate(&self, keys: &str) -> Result<String, Panic> {
+        let mut keys = keys.to_string();
+        for robot in (1..=(self.robots + 1)).rev() {
+            let pad = if robot == 1 {Pad::Numeric} else {Pad::Directional};
+            keys = replay(self.keypad(pad), &keys).map_err(|key| Panic {robot, key})?;
+        }
+        Ok(keys)
+    }
 }
 
-fn part_1(puzzle: &Puzzle) -> usize {
-    // let mut robots = puzzle.robots.clone();
-    let dpad_0 = Keypad::load(DIRECTIONAL_KEYPAD);
-    let npad = Keypad::load(NUMERIC_KEYPAD);
-    // println!("{:?}", get_dirs(&npad, npad.get_map().find('A').unwrap(), npad.get_map().find('4').unwrap()));
-    println!("{:?}", get_dirs(&npad, npad.get_map().find('7').unwrap(), npad.get_map().find('0').unwrap()));
-    
-    todo!("part 1");
+// sequences are only shown for that many robots, they get long quickly
+const SHOWN_ROBOTS: usize = 3;
+
+fn complexity(puzzle: &Puzzle, robots: usize) -> u64 {
+    let mut conductor = Conductor::new(robots);
+    puzzle.codes.iter().map(|code| {
+        if is_verbose() && (robots <= SHOWN_ROBOTS) {
+            let sequences = conductor.sequences(code);
+            diagnostic!("{code}:");
+            for (i, keys) in sequences.iter().enumerate() {
+                if i + 1 < sequences.len() {
+                    diagnostic!("  robot {}: {keys}", i + 1);
+                } else {
+                    diagnostic!("  you: {keys}");
+                }
+            }
+            match conductor.simulate(&sequences[sequences.len() - 1]) {
+                Ok(door) => diagnostic!("  door: {door}"),
+                Err(e) => diagnostic!("  {e}")
+            }
+        }
+        let n: u64 = code.trim_end_matches('A').parse().expect("numeric code");
+        conductor.presses(code) * n
+    }).sum()
 }
 
-fn part_2(puzzle: &Puzzle) -> usize {
-    todo!("part 2");
+fn part_1(puzzle: &Puzzle) -> u64 {
+    complexity(puzzle, 2)
 }
 
-pub(crate) fn solve() {
-    // let puzzle = Puzzle::load();
-    let puzzle = Puzzle {};
-    part_1(&puzzle);
-    part_2(&puzzle);
+fn part_2(puzzle: &Puzzle) -> u64 {
+    complexity(puzzle, 25)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_get_dirs() {
+        let pad = Keypad::load(NUMERIC_KEYPAD);
+        let dirs = get_dirs(&pad, 'A', '4');
+        assert_eq!(dirs.len(), 5);
+        assert!(dirs.iter().all(|d| (d.len() == 4) && (d.matches('<').count() == 2)));
+        assert!(!dirs.contains(&"<<^^".to_string()));
+        assert_eq!(get_dirs(&pad, '0', '0'), [""]);
+
+        let pad = Keypad::load(DIRECTIONAL_KEYPAD);
+        assert_eq!(get_dirs(&pad, '<', '^'), [">^"]);
+    }
+
+    #[test]
+    fn test_presses() {
+        let mut conductor = Conductor::new(0);
+        assert_eq!(conductor.presses("029A"), 12);
+        let mut conductor = Conductor::new(1);
+        assert_eq!(conductor.presses("029A"), 28);
+        let mut conductor = Conductor::new(2);
+        assert_eq!(conductor.presses("029A"), 68);
+        assert_eq!(conductor.presses("980A"), 60);
+        assert_eq!(conductor.presses("179A"), 68);
+        assert_eq!(conductor.presses("456A"), 64);
+        assert_eq!(conductor.presses("379A"), 64);
+    }
+
+    #[test]
+    fn test_sequences() {
+        let mut conductor = Conductor::new(2);
+        for code in ["029A", "980A", "179A", "456A", "379A"] {
+            let sequences = conductor.sequences(code);
+            assert_eq!(sequences.len(), 4);
+            assert_eq!(sequences[3].len() as u64, conductor.presses(code));
+            assert_eq!(conductor.simulate(&sequences[3]), Ok(code.to_string()));
+        }
+        let sequences = Conductor::new(0).sequences("029A");
+        assert_eq!(sequences[1].len(), 12);
+        assert!(["<A^A>^^AvvvA", "<A^A^>^AvvvA", "<A^A^^>AvvvA"].contains(&sequences[1].as_str()));
+    }
+
+    #[test]
+    fn test_simulate() {
+        let conductor = Conductor::new(2);
+        let keys = "<vA<AA>>^AvAA<^A>A<v<A>>^AvA^A<vA>^A<v<A>^A>AAvA^A<v<A>A>^AAAvA<^A>A";
+        assert_eq!(conductor.simulate(keys), Ok("029A".to_string()));
+        assert_eq!(conductor.simulate("<<A"), Err(Panic {robot: 3, key: 1}));
+        assert_eq!(conductor.simulate("<A"), Err(Panic {robot: 2, key: 0}));
+        assert_eq!(conductor.simulate("v<<A"), Ok(String::new()));
+        assert_eq!(Conductor::new(0).simulate("<A^A<<"), Err(Panic {robot: 1, key: 5}));
+        assert_eq!(Conductor::new(0).simulate("<A^A<<").unwrap_err().to_string(), "robot 1 panicked at key 6");
+        assert_eq!(Conductor::new(0).simulate("<x"), Err(Panic {robot: 1, key: 1}));
+    }
+
     #[test]
     fn test_part_1() {
+        let puzzle = include_str!("../../data/day_21/test.txt");
+        let puzzle = Puzzle::load(puzzle);
+        assert_eq!(part_1(&puzzle), 126384);
     }
 
     #[test]
     fn test_part_2() {
+        let puzzle = include_str!("../../data/day_21/test.txt");
+        let puzzle = Puzzle::load(puzzle);
+        assert_eq!(part_2(&puzzle), 154115708116294);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse() {
+        let e = Puzzle::parse("029A\n98xA").err().unwrap();
+        assert_eq!((e.line, e.column), (2, 3));
+    }
+}
@@ -1,18 +1,27 @@
+//! Day 22: Monkey Market.
+
 use std::collections::{HashMap, HashSet};
 
-struct Puzzle {
+use crate::aoc::{Answer, Solution, parse::{self, ParseError}};
+
+/// The initial secret numbers of the buyers.
+pub struct Puzzle {
     secrets: Vec<u64>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
-        let secrets: Result<_, _> = data.trim().lines().map(|s| s.trim().parse()).collect();
-        let secrets = secrets.ok()?;
-        Some(Puzzle {secrets})
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let secrets: Result<_, _> = data.trim().lines().map(|s| parse::value(data, s.trim(), "a secret number")).collect();
+        let secrets = secrets?;
+        Ok(Puzzle {secrets})
+    }
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_1(self))))
     }
 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(part_2(self))))
     }
 }
 
@@ -68,13 +77,6 @@ fn part_2(puzzle: &Puzzle) -> u64 {
     part_2_n(puzzle, 2000).0
 }
 
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_22/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1:  {}", part_1(&puzzle));
-    println!("part 2:  {}", part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,10 @@
-use std::collections::HashMap;
+//! Day 24: Crossed Wires.
 
-#[derive(Clone, Copy, Debug)]
+use std::collections::{HashMap, HashSet};
+
+use crate::aoc::{Answer, Solution, diagnostic, parse::{self, ParseError}};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum Op {
     And,
     Or,
@@ -12,65 +16,76 @@ struct Wire {
     name: u32,
     state: bool
 }
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Gate {
     op: Op,
     inputs: (u32, u32),
     output: u32
 }
 
-struct Puzzle {
+/// The initial wire values and the gates.
+pub struct Puzzle {
     wires: Vec<Wire>,
     gates: Vec<Gate>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
         let data = data.trim().replace("\r", "");
-        let parse_wire = |s: &str| {
-            let (name, state) = s.split_once(": ")?;
-            if name.len() != 3 {
-                return None;
-            }
-            if (state != "0") && (state != "1") {
-                return None;
+        let parse_name = |s: &str| {
+            if s.is_ascii() && (s.len() == 3) {
+                Ok(Self::encode_name(s))
+            } else {
+                Err(ParseError::at(&data, s, "a three letter wire name"))
             }
-            let name = Self::encode_name(name);
-            let state = state == "1";
-            Some(Wire {name, state})
+        };
+        let parse_wire = |s: &str| {
+            let (name, state) = parse::split_once(&data, s, ": ")?;
+            let name = parse_name(name)?;
+            let state = match state {
+                "0" => false,
+                "1" => true,
+                _ => return Err(ParseError::at(&data, state, "0 or 1"))
+            };
+            Ok(Wire {name, state})
         };
         let parse_gate = |s: &str| {
-            let (aob, output) = s.split_once(" -> ")?;
-            let output = Self::encode_name(output);
-            let mut aob = aob.split(" ");
-            let a = aob.next()?;
-            let op = aob.next()?;
-            let b = aob.next()?;
-            if aob.next().is_some() {return None;}
-            if (a.len() != 3) || (a.len() != 3) {return None;}
-            let a = Self::encode_name(a);
-            let b = Self::encode_name(b);
+            let (aob, output) = parse::split_once(&data, s, " -> ")?;
+            let output = parse_name(output)?;
+            let mut items = aob.split(" ");
+            let a = parse_name(parse::next(&data, aob, &mut items, "an input wire")?)?;
+            let op = parse::next(&data, aob, &mut items, "a gate")?;
+            let b = parse_name(parse::next(&data, aob, &mut items, "an input wire")?)?;
+            if let Some(s) = items.next() {
+                return Err(ParseError::at(&data, s, "' -> '"));
+            }
             let op = match op {
                 "AND" => Op::And,
                 "OR"  => Op::Or,
                 "XOR" => Op::Xor,
-                _ => return None
+                _ => return Err(ParseError::at(&data, op, "AND, OR or XOR"))
             };
             let inputs = (a, b);
-            Some(Gate {op, inputs, output})
+            Ok(Gate {op, inputs, output})
         };
-        let (wires, gates) = data.split_once("\n\n")?;
-        let wires: Option<_> = wires.trim().lines().map(parse_wire).collect();
+        let (wires, gates) = data.split_once("\n\n").ok_or_else(|| ParseError::after(&data, &data, "an empty line before the gates"))?;
+        let wires: Result<_, _> = wires.trim().lines().map(parse_wire).collect();
         let wires = wires?;
-        let gates: Option<_> = gates.trim().lines().map(parse_gate).collect();
+        let gates: Result<_, _> = gates.trim().lines().map(parse_gate).collect();
         let gates = gates?;
-        Some(Puzzle {wires, gates})
+        Ok(Puzzle {wires, gates})
     }
- 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_1(self)?)))
     }
 
+    fn part_2(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_2(self)?)))
+    }
+}
+
+impl Puzzle {
     fn encode_name(name: &str) -> u32 {
         let mut name = name.bytes();
         let (a, b, c) = (
@@ -85,64 +100,344 @@ impl Puzzle {
         let (a, b, c) = (a as u8, b as u8, c as u8);
         unsafe {String::from_utf8_unchecked(vec![a, b, c])}
     }
-}
 
-fn part_1(puzzle: &Puzzle) -> Option<u64> {
-    let mut wires = HashMap::new();
-    for w in &puzzle.wires {
-        wires.insert(w.name, w.state);
-    }
-    let mut gates = HashMap::new();
-    for g in &puzzle.gates {
-        gates.insert(g.output, g);
-    }
-    let mut compute: Vec<_> = gates.iter()
-        .filter(|(n, _)| ((*n >> 16) as u8) == b'z')
-        .map(|(n, g)| (*n, g)).collect();
-    while !compute.is_empty() {
-        let (n, g) = compute.pop().unwrap();
-        let i0 = wires.contains_key(&g.inputs.0);
-        let i1 = wires.contains_key(&g.inputs.1);
-        if i0 && i1 {
-            let i0 = wires.get(&g.inputs.0).unwrap();
-            let i1 = wires.get(&g.inputs.1).unwrap();
-            let s = match g.op {
-                Op::And => i0 & i1,
-                Op::Or => i0 | i1,
-                Op::Xor => i0 ^ i1
+    /// The gates as a circuit to evaluate.
+    pub fn circuit(&self) -> Result<Circuit, CircuitError> {
+        Circuit::new(self.gates.clone())
+    }
+
+    /// The gates as a Graphviz DOT graph, gates are nodes shaped and coloured
+    /// by operation and wires are edges labelled with their names, the wires
+    /// named in `highlight` are drawn in red.
+    pub fn to_dot(&self, highlight: &[&str]) -> String {
+        let drivers: HashMap<_, _> = self.gates.iter().enumerate().map(|(i, g)| (g.output, i)).collect();
+        let used: HashSet<_> = self.gates.iter().flat_map(|g| [g.inputs.0, g.inputs.1]).collect();
+        let style = |name: &str| if highlight.contains(&name) {", color=red, fontcolor=red, penwidth=2"} else {""};
+        let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n");
+        let mut wires: Vec<_> = used.iter().filter(|w| !drivers.contains_key(w)).collect();
+        wires.extend(self.gates.iter().map(|g| &g.output).filter(|w| !used.contains(w)));
+        wires.sort();
+        for w in wires {
+            let name = Self::decode_name(*w);
+            dot.push_str(&format!("    {} [shape=plaintext{}];\n", dot_id(&name), style(&name)));
+        }
+        for (i, g) in self.gates.iter().enumerate() {
+            let (label, shape, colour) = match g.op {
+                Op::And => ("AND", "box", "lightblue"),
+                Op::Or => ("OR", "ellipse", "lightgreen"),
+                Op::Xor => ("XOR", "diamond", "gold")
             };
-            wires.insert(n, s);
-        } else {
-            compute.push((n, g));
-            if !i0 {
-                let g = gates.get(&g.inputs.0)?;
-                compute.push((g.output, g));
+            dot.push_str(&format!("    \"g{i}\" [label=\"{label}\", shape={shape}, style=filled, fillcolor={colour}];\n"));
+        }
+        // an edge from the driver of each wire, gate or input, to each gate using it
+        let node = |w: u32| drivers.get(&w).map_or(dot_id(&Self::decode_name(w)), |i| format!("\"g{i}\""));
+        for (i, g) in self.gates.iter().enumerate() {
+            for w in [g.inputs.0, g.inputs.1] {
+                let name = Self::decode_name(w);
+                dot.push_str(&format!("    {} -> \"g{i}\" [label={}{}];\n", node(w), dot_id(&name), style(&name)));
             }
-            if !i1 {
-                let g = gates.get(&g.inputs.1)?;
-                compute.push((g.output, g));
+            if !used.contains(&g.output) {
+                let name = Self::decode_name(g.output);
+                let id = dot_id(&name);
+                dot.push_str(&format!("    \"g{i}\" -> {id} [label={id}{}];\n", style(&name)));
             }
         }
+        dot.push_str("}\n");
+        dot
     }
-    let mut zs: Vec<_> = wires.into_iter().filter(|(n, _)| ((n >> 16) as u8) == b'z').collect();
-    zs.sort();
-    zs.reverse();
-    Some(zs.into_iter().fold(0, |a, (_, b)| (a << 1) | b as u64))
 }
 
-fn solve_part_1(puzzle: &Puzzle) -> u64 {
-    part_1(puzzle).expect("solvable puzzle")
+// a quoted DOT identifier, wire names may start with a digit or hold any character
+fn dot_id(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
 }
 
-fn solve_part_2(puzzle: &Puzzle) -> u32 {
-    todo!("part 2")
+fn swap_outputs(gates: &mut [Gate], a: u32, b: u32) {
+    for g in gates {
+        if g.output == a {
+            g.output = b;
+        } else if g.output == b {
+            g.output = a;
+        }
+    }
 }
 
-pub(crate) fn solve() {
-    let puzzle = include_str!("../../data/day_24/input.txt");
-    let puzzle = Puzzle::load(puzzle);
-    println!("part 1: {}", solve_part_1(&puzzle));
-    println!("part 2: {}", solve_part_2(&puzzle));
+/// Why a circuit cannot be evaluated, with the name of the wire at fault.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CircuitError {
+    /// The wire is an input of a gate it depends on.
+    Cycle(String),
+    /// The wire is neither an input nor the output of a gate.
+    Undriven(String),
+    /// The wire is an input or the output of several gates.
+    Driven(String),
+    /// The wire is a bit of x, y or z past the 64 bits of their values.
+    TooWide(String)
+}
+
+impl CircuitError {
+    fn cycle(w: u32) -> Self {
+        Self::Cycle(Puzzle::decode_name(w))
+    }
+
+    fn undriven(w: u32) -> Self {
+        Self::Undriven(Puzzle::decode_name(w))
+    }
+
+    fn driven(w: u32) -> Self {
+        Self::Driven(Puzzle::decode_name(w))
+    }
+}
+
+impl std::fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Cycle(w) => write!(f, "wire {w} is on a cycle"),
+            Self::Undriven(w) => write!(f, "wire {w} is not driven"),
+            Self::Driven(w) => write!(f, "wire {w} is driven more than once"),
+            Self::TooWide(w) => write!(f, "wire {w} is past bit 63")
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
+
+// the bit of a wire named like `x07`
+fn wire_bit(name: u32, c: char) -> Option<usize> {
+    let name = Puzzle::decode_name(name);
+    name.strip_prefix(c)?.parse().ok()
+}
+
+/// The gates sorted so that each one comes after the gates driving its
+/// inputs, evaluated with any x and y, see [`Puzzle::circuit`].
+#[derive(Clone, Debug)]
+pub struct Circuit {
+    source: Vec<Gate>,
+    // operation, inputs and output of each gate, wires are indices in the values
+    gates: Vec<(Op, usize, usize, usize)>,
+    wires: usize,
+    // bits and wires of x, y and z
+    x: Vec<(usize, usize)>,
+    y: Vec<(usize, usize)>,
+    z: Vec<(usize, usize)>
+}
+
+impl Circuit {
+    fn new(source: Vec<Gate>) -> Result<Self, CircuitError> {
+        let is_input = |w: u32| wire_bit(w, 'x').or(wire_bit(w, 'y')).is_some();
+        let mut drivers = HashMap::new();
+        for (i, g) in source.iter().enumerate() {
+            if is_input(g.output) || drivers.insert(g.output, i).is_some() {
+                return Err(CircuitError::driven(g.output));
+            }
+        }
+
+        // depth first, a gate is done once the gates driving it are, the ones
+        // started and not done are the ones being followed back
+        let (todo, started, done) = (0, 1, 2);
+        let mut state = vec![todo; source.len()];
+        let mut order = Vec::with_capacity(source.len());
+        for first in 0..source.len() {
+            let mut stack = vec![(first, false)];
+            while let Some((i, inputs_done)) = stack.pop() {
+                if inputs_done {
+                    state[i] = done;
+                    order.push(i);
+                    continue;
+                }
+                if state[i] != todo {
+                    continue;
+                }
+                state[i] = started;
+                stack.push((i, true));
+                let (a, b) = source[i].inputs;
+                for w in [a, b].into_iter().filter(|w| !is_input(*w)) {
+                    let j = *drivers.get(&w).ok_or_else(|| CircuitError::undriven(w))?;
+                    if state[j] == started {
+                        return Err(CircuitError::cycle(w));
+                    }
+                    stack.push((j, false));
+                }
+            }
+        }
+
+        let mut index = HashMap::new();
+        let mut wire = |w: u32| {
+            let n = index.len();
+            *index.entry(w).or_insert(n)
+        };
+        let gates = order.into_iter().map(|i| {
+            let g = &source[i];
+            (g.op, wire(g.inputs.0), wire(g.inputs.1), wire(g.output))
+        }).collect();
+        let bits = |c| -> Result<Vec<_>, _> {
+            index.iter().filter_map(|(w, i)| Some((*w, wire_bit(*w, c)?, *i))).map(|(w, bit, i)| match bit {
+                0..64 => Ok((bit, i)),
+                _ => Err(CircuitError::TooWide(Puzzle::decode_name(w)))
+            }).collect()
+        };
+        let (x, y, z) = (bits('x')?, bits('y')?, bits('z')?);
+        Ok(Self {wires: index.len(), source, gates, x, y, z})
+    }
+
+    /// The same circuit with the outputs of the wires `a` and `b` swapped.
+    pub fn swap(&self, a: &str, b: &str) -> Result<Self, CircuitError> {
+        let output = |name: &str| {
+            let w = Some(name).filter(|n| n.is_ascii() && (n.len() == 3)).map(Puzzle::encode_name);
+            w.filter(|w| self.source.iter().any(|g| g.output == *w)).ok_or_else(|| CircuitError::Undriven(name.to_string()))
+        };
+        let mut gates = self.source.clone();
+        swap_outputs(&mut gates, output(a)?, output(b)?);
+        Self::new(gates)
+    }
+
+    /// Whether z is x + y, tried on sums carrying through each bit.
+    pub fn adds(&self) -> bool {
+        let bits = self.x.len().min(self.y.len());
+        let mask = u64::MAX.checked_shr(64 - bits as u32).unwrap_or(0);
+        let mut pairs = vec![(0, 0), (mask, 1), (1, mask), (mask, mask), (0x5555_5555_5555 & mask, 0xaaaa_aaaa_aaaa & mask)];
+        pairs.extend((0..bits).map(|bit| (1 << bit, 1 << bit)));
+        pairs.into_iter().all(|(x, y)| self.eval(x, y) as u128 == x as u128 + y as u128)
+    }
+
+    /// The value of z for the given x and y, the bits of x and y without a
+    /// wire are ignored.
+    pub fn eval(&self, x: u64, y: u64) -> u64 {
+        let mut values = vec![false; self.wires];
+        for (bit, w) in &self.x {
+            values[*w] = ((x >> bit) & 1) == 1;
+        }
+        for (bit, w) in &self.y {
+            values[*w] = ((y >> bit) & 1) == 1;
+        }
+        for &(op, a, b, o) in &self.gates {
+            values[o] = match op {
+                Op::And => values[a] & values[b],
+                Op::Or => values[a] | values[b],
+                Op::Xor => values[a] ^ values[b]
+            };
+        }
+        self.z.iter().fold(0, |z, (bit, w)| z | ((values[*w] as u64) << bit))
+    }
+}
+
+fn part_1(puzzle: &Puzzle) -> Result<u64, CircuitError> {
+    let circuit = puzzle.circuit()?;
+    let value = |c| puzzle.wires.iter().filter_map(|w| Some((wire_bit(w.name, c)?, w.state))).fold(0, |v, (bit, s)| v | ((s as u64) << bit));
+    Ok(circuit.eval(value('x'), value('y')))
+}
+
+fn solve_part_1(puzzle: &Puzzle) -> Result<u64, String> {
+    part_1(puzzle).map_err(|e| format!("invalid circuit, {e}"))
+}
+
+// name of the wire `c` of a bit, like `z07`
+fn bit_name(c: char, bit: usize) -> u32 {
+    Puzzle::encode_name(&format!("{c}{bit:02}"))
+}
+
+// output of the gate `a op b`
+fn find_gate(gates: &[Gate], op: Op, a: u32, b: u32) -> Option<u32> {
+    gates.iter().find(|g| (g.op == op) && ((g.inputs == (a, b)) || (g.inputs == (b, a)))).map(|g| g.output)
+}
+
+// other input of a gate `op` with `a` as an input
+fn other_input(gates: &[Gate], op: Op, a: u32) -> Option<u32> {
+    gates.iter().filter(|g| g.op == op).find_map(|g| match g.inputs {
+        (i, o) | (o, i) if i == a => Some(o),
+        _ => None
+    })
+}
+
+// wire expected where `a` is, given the gate `a op b` does not exist
+fn misplaced(gates: &[Gate], op: Op, a: u32, b: u32) -> Option<(u32, u32)> {
+    match other_input(gates, op, b) {
+        Some(o) => Some((a, o)),
+        None => Some((b, other_input(gates, op, a)?))
+    }
+}
+
+// checks the full adder of a bit, each bit adds `x XOR y` and the carry of
+// the bit before into `z`, and carries `(x AND y) OR ((x XOR y) AND carry)`,
+// the first bit is a half adder without carry
+//
+// gives the carry out of the bit, or two wires to swap to get further
+fn check_bit(gates: &[Gate], bit: usize, carry: Option<u32>) -> Option<Result<u32, (u32, u32)>> {
+    let (x, y, z) = (bit_name('x', bit), bit_name('y', bit), bit_name('z', bit));
+    let sum = find_gate(gates, Op::Xor, x, y)?;
+    let carry_xy = find_gate(gates, Op::And, x, y)?;
+    let Some(carry) = carry else {
+        return Some(if sum == z {Ok(carry_xy)} else {Err((sum, z))});
+    };
+    let Some(out) = find_gate(gates, Op::Xor, sum, carry) else {
+        return misplaced(gates, Op::Xor, sum, carry).map(Err);
+    };
+    if out != z {
+        return Some(Err((out, z)));
+    }
+    let carry_sum = find_gate(gates, Op::And, sum, carry)?;
+    match find_gate(gates, Op::Or, carry_xy, carry_sum) {
+        Some(carry) => Some(Ok(carry)),
+        None => misplaced(gates, Op::Or, carry_xy, carry_sum).map(Err)
+    }
+}
+
+// two gate outputs found swapped, and the bit of the adder they broke
+#[derive(Debug, PartialEq)]
+struct Swap {
+    bit: usize,
+    wires: (u32, u32)
+}
+
+// swaps putting the gates back into a ripple carry adder of x and y into z,
+// bit by bit from the lowest one, `None` when the circuit is too far from it
+fn check_adder(puzzle: &Puzzle) -> Option<Vec<Swap>> {
+    let mut gates = puzzle.gates.clone();
+    let bits = puzzle.wires.iter().filter(|w| ((w.name >> 16) as u8) == b'x').count();
+    let mut swaps = Vec::new();
+    let mut swap = |gates: &mut [Gate], bit, (a, b)| {
+        swap_outputs(gates, a, b);
+        swaps.push(Swap {bit, wires: (a, b)});
+    };
+    let mut carry = None;
+    let mut tries = gates.len();
+    let mut bit = 0;
+    while bit < bits {
+        match check_bit(&gates, bit, carry)? {
+            Ok(next) => {
+                carry = Some(next);
+                bit += 1;
+            }
+            Err((a, b)) if tries > 0 => {
+                swap(&mut gates, bit, (a, b));
+                carry = carry.map(|c| if c == a {b} else if c == b {a} else {c});
+                tries -= 1;
+            }
+            Err(_) => return None
+        }
+    }
+    // the last carry is the highest bit of z
+    let (carry, z) = (carry?, bit_name('z', bits));
+    if carry != z {
+        swap(&mut gates, bits, (carry, z));
+    }
+    Some(swaps)
+}
+
+fn solve_part_2(puzzle: &Puzzle) -> Result<String, String> {
+    let swaps = check_adder(puzzle).ok_or("the gates are not an adder with swapped outputs")?;
+    let mut circuit = puzzle.circuit().map_err(|e| format!("invalid circuit, {e}"))?;
+    for Swap {bit, wires: (a, b)} in &swaps {
+        let (a, b) = (Puzzle::decode_name(*a), Puzzle::decode_name(*b));
+        diagnostic!("bit {bit}: swapped {a} and {b}");
+        circuit = circuit.swap(&a, &b).map_err(|e| format!("invalid circuit after swaps, {e}"))?;
+    }
+    if !circuit.adds() {
+        return Err("circuit still not adding after swaps".to_string());
+    }
+    let mut wires: Vec<_> = swaps.iter().flat_map(|s| [s.wires.0, s.wires.1]).map(Puzzle::decode_name).collect();
+    wires.sort();
+    Ok(wires.join(","))
 }
 
 #[cfg(test)]
@@ -153,6 +448,135 @@ mod tests {
     fn test_part_1() {
         let puzzle = include_str!("../../data/day_24/test.txt");
         let puzzle = Puzzle::load(puzzle);
-        assert!(solve_part_1(&puzzle) == 2024);
+        assert!(solve_part_1(&puzzle) == Ok(2024));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let puzzle = Puzzle::load("x00: 1\ny00: 0\n\nx00 XOR y00 -> z00\nx00 AND y00 -> abc\nabc OR x00 -> z01\n");
+        let dot = puzzle.to_dot(&["abc"]);
+        let lines: Vec<_> = dot.lines().map(|s| s.trim()).collect();
+        assert_eq!(lines, [
+            "digraph circuit {",
+            "rankdir=LR;",
+            "\"x00\" [shape=plaintext];",
+            "\"y00\" [shape=plaintext];",
+            "\"z00\" [shape=plaintext];",
+            "\"z01\" [shape=plaintext];",
+            "\"g0\" [label=\"XOR\", shape=diamond, style=filled, fillcolor=gold];",
+            "\"g1\" [label=\"AND\", shape=box, style=filled, fillcolor=lightblue];",
+            "\"g2\" [label=\"OR\", shape=ellipse, style=filled, fillcolor=lightgreen];",
+            "\"x00\" -> \"g0\" [label=\"x00\"];",
+            "\"y00\" -> \"g0\" [label=\"y00\"];",
+            "\"g0\" -> \"z00\" [label=\"z00\"];",
+            "\"x00\" -> \"g1\" [label=\"x00\"];",
+            "\"y00\" -> \"g1\" [label=\"y00\"];",
+            "\"g1\" -> \"g2\" [label=\"abc\", color=red, fontcolor=red, penwidth=2];",
+            "\"x00\" -> \"g2\" [label=\"x00\"];",
+            "\"g2\" -> \"z01\" [label=\"z01\"];",
+            "}"
+        ]);
+
+        // names DOT would not take bare
+        let puzzle = Puzzle::load("x00: 1\n1ab: 0\n\nx00 AND 1ab -> a\"b\n");
+        let dot = puzzle.to_dot(&[]);
+        assert!(dot.contains("    \"1ab\" -> \"g0\" [label=\"1ab\"];\n"));
+        assert!(dot.contains("    \"g0\" -> \"a\\\"b\" [label=\"a\\\"b\"];\n"));
+    }
+
+    // a ripple carry adder of `bits` bits, with `swaps` outputs swapped
+    fn adder(bits: usize, swaps: &[(&str, &str)]) -> String {
+        let mut data = String::new();
+        for i in 0..bits {
+            data.push_str(&format!("x{i:02}: 1\ny{i:02}: 0\n"));
+        }
+        data.push('\n');
+        for i in 0..bits {
+            let c = if i == 0 {String::new()} else {format!("c{:02}", i - 1)};
+            let co = if i == bits - 1 {format!("z{bits:02}")} else {format!("c{i:02}")};
+            let mut gates = vec![
+                format!("x{i:02} XOR y{i:02} -> s{i:02}"),
+                format!("y{i:02} AND x{i:02} -> a{i:02}")
+            ];
+            if i == 0 {
+                gates = vec![format!("x00 XOR y00 -> z00"), format!("x00 AND y00 -> {co}")];
+            } else {
+                gates.push(format!("{c} XOR s{i:02} -> z{i:02}"));
+                gates.push(format!("s{i:02} AND {c} -> b{i:02}"));
+                gates.push(format!("a{i:02} OR b{i:02} -> {co}"));
+            }
+            for gate in gates {
+                let (ops, output) = gate.split_once(" -> ").unwrap();
+                let output = swaps.iter().fold(output, |o, (a, b)| if o == *a {b} else if o == *b {a} else {o});
+                data.push_str(&format!("{ops} -> {output}\n"));
+            }
+        }
+        data
+    }
+
+    fn swapped(data: &str) -> Vec<(usize, String, String)> {
+        let swaps = check_adder(&Puzzle::load(data)).unwrap();
+        swaps.into_iter().map(|s| {
+            let (a, b) = (Puzzle::decode_name(s.wires.0), Puzzle::decode_name(s.wires.1));
+            (s.bit, a.clone().min(b.clone()), a.max(b))
+        }).collect()
+    }
+
+    #[test]
+    fn test_circuit() {
+        let puzzle = Puzzle::load(&adder(6, &[]));
+        let circuit = puzzle.circuit().unwrap();
+        assert_eq!(circuit.eval(13, 29), 42);
+        assert_eq!(circuit.eval(63, 63), 126);
+        assert!(circuit.adds());
+
+        let (a, b) = ("z02", "b02");
+        let swapped = circuit.swap(a, b).unwrap();
+        assert_eq!(swapped.eval(1, 0), 1);
+        assert_eq!(swapped.eval(2, 2), 8);
+        assert!(!swapped.adds());
+        assert!(swapped.swap(a, b).unwrap().adds());
+
+        assert!(matches!(circuit.swap("z03", "c01"), Err(CircuitError::Cycle(_))));
+        assert_eq!(circuit.swap("z03", "x01").err(), Some(CircuitError::Undriven("x01".to_string())));
+        assert_eq!(circuit.swap("z03", "z0").err(), Some(CircuitError::Undriven("z0".to_string())));
+
+        let puzzle = Puzzle::load("x00: 1\ny00: 0\n\nx00 XOR abc -> z00\n");
+        let e = puzzle.circuit().err().unwrap();
+        assert_eq!(e, CircuitError::Undriven("abc".to_string()));
+        assert_eq!(e.to_string(), "wire abc is not driven");
+
+        let puzzle = Puzzle::load("x00: 1\ny00: 0\n\nx00 XOR y00 -> z00\nx00 AND y00 -> z00\n");
+        assert_eq!(puzzle.circuit().err(), Some(CircuitError::Driven("z00".to_string())));
+
+        let puzzle = Puzzle::load("x00: 1\ny00: 0\n\nx00 XOR y00 -> z64\n");
+        let e = puzzle.circuit().err().unwrap();
+        assert_eq!(e, CircuitError::TooWide("z64".to_string()));
+        assert_eq!(e.to_string(), "wire z64 is past bit 63");
+        let puzzle = Puzzle::load("x99: 1\ny00: 0\n\nx99 XOR y00 -> z00\n");
+        assert_eq!(puzzle.circuit().err(), Some(CircuitError::TooWide("x99".to_string())));
+    }
+
+    #[test]
+    fn test_check_adder() {
+        let data = adder(6, &[]);
+        assert_eq!(solve_part_1(&Puzzle::load(&data)), Ok(0b111111));
+        assert!(swapped(&data).is_empty());
+
+        let data = adder(6, &[("s01", "a01")]);
+        assert_eq!(swapped(&data), [(1, "a01".to_string(), "s01".to_string())]);
+
+        let data = adder(6, &[("z02", "b02"), ("c03", "z04"), ("z00", "c00")]);
+        assert_eq!(swapped(&data), [
+            (0, "c00".to_string(), "z00".to_string()),
+            (2, "b02".to_string(), "z02".to_string()),
+            (4, "c03".to_string(), "z04".to_string())
+        ]);
+
+        let data = adder(6, &[("z06", "s05")]);
+        assert_eq!(swapped(&data), [(5, "s05".to_string(), "z06".to_string())]);
+
+        let puzzle = Puzzle::load(&adder(6, &[("z03", "c03"), ("a04", "s04")]));
+        assert_eq!(solve_part_2(&puzzle), Ok("a04,c03,s04,z03".to_string()));
     }
 }
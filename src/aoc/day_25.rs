@@ -1,72 +1,76 @@
-use crate::aoc::grid::Grid;
+//! Day 25: Code Chronicle.
 
-struct Puzzle
+use crate::aoc::{Answer, Solution, grid::Grid, parse::ParseError};
+
+/// The lock and key schematics.
+pub struct Puzzle
 {
     locks: Vec<[u8; 5]>,
     keys: Vec<[u8; 5]>
 }
 
-impl Puzzle {
-    fn parse(data: &str) -> Option<Self> {
+impl Solution for Puzzle {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let data = data.trim().replace("\r", "");
         #[derive(PartialEq)]
         enum LK {L, K}
-        struct LKS {
+        struct Lks {
             lk: LK,
             schema: [u8; 5]
         }
         let parse_lk = |d: &str| {
-            let grid: Grid<char> = Grid::parse(d, "")?;
-            if grid.size() != (5, 7) {return None;}
-            if grid.get((0, 0)) == grid.get((0, 6)) {return None;}
-            if (1..5).any(|x| grid.get((x, 0)) != grid.get((0, 0))) {return None;}
-            if (1..5).any(|x| grid.get((x, 6)) != grid.get((0, 6))) {return None;}
+            let invalid = || ParseError::at(&data, d, "a 5x7 lock or key schematic");
+            let grid: Grid<char> = Grid::parse(d, "").map_err(|e| e.within(&data, d))?;
+            if grid.size() != (5, 7) {return Err(invalid());}
+            if grid.get((0, 0)) == grid.get((0, 6)) {return Err(invalid());}
+            if (1..5).any(|x| grid.get((x, 0)) != grid.get((0, 0))) {return Err(invalid());}
+            if (1..5).any(|x| grid.get((x, 6)) != grid.get((0, 6))) {return Err(invalid());}
             for y in 0..7 {
                 for x in 0..5 {
                     if !"#.".contains(grid.get((x, y))) {
-                        return None;
+                        return Err(invalid());
                     }
                 }
             }
-            let lks: LKS = if grid.get((0, 0)) == '#' {
+            let lks: Lks = if grid.get((0, 0)) == '#' {
                 let mut s = [0u8; 5];
-                for x in 0..5 {
+                for (x, s) in s.iter_mut().enumerate() {
                     for y in 1..6 {
                         if grid.get((x, y)) != '#' {break;}
-                        s[x] += 1;
+                        *s += 1;
                     }
-                    for y in (s[x]as usize + 1)..6 {
-                        if grid.get((x, y)) != '.' {return None;}
+                    for y in (*s as usize + 1)..6 {
+                        if grid.get((x, y)) != '.' {return Err(invalid());}
                     }
                 }
-                LKS {lk: LK::L, schema: s}
+                Lks {lk: LK::L, schema: s}
             } else {
                 let mut s = [0u8; 5];
-                for x in 0..5 {
+                for (x, s) in s.iter_mut().enumerate() {
                     for y in 1..6 {
                         if grid.get((x, 6 - y)) != '#' {break;}
-                        s[x] += 1;
+                        *s += 1;
                     }
-                    for y in (s[x]as usize + 1)..6 {
-                        if grid.get((x, 6 - y)) != '.' {return None;}
+                    for y in (*s as usize + 1)..6 {
+                        if grid.get((x, 6 - y)) != '.' {return Err(invalid());}
                     }
                 }
-                LKS {lk: LK::K, schema: s}
+                Lks {lk: LK::K, schema: s}
             };
-            Some(lks)
+            Ok(lks)
         };
-        let data = data.trim().replace("\r", "");
-        let lkss: Option<Vec<_>> = data.split("\n\n").map(parse_lk).collect();
+        let lkss: Result<Vec<_>, _> = data.split("\n\n").map(parse_lk).collect();
         let lkss = lkss?;
         let (locks, keys): (Vec<_>, Vec<_>) = lkss.iter().partition(|lks| lks.lk == LK::L);
         let mut locks: Vec<_> = locks.into_iter().map(|lks| lks.schema).collect();
         let mut keys: Vec<_> = keys.into_iter().map(|lks| lks.schema).collect();
         locks.sort();
         keys.sort();
-        Some(Puzzle {locks, keys})
+        Ok(Puzzle {locks, keys})
     }
- 
-    fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+
+    fn part_1(&self) -> Result<Option<Answer>, String> {
+        Ok(Some(Box::new(solve_part_1(self))))
     }
 }
 
@@ -78,17 +82,6 @@ fn solve_part_1(puzzle: &Puzzle) -> usize {
     }).sum()
 }
 
-fn solve_part_2(puzzle: &Puzzle) -> u32 {
-    todo!("part 2")
-}
-
-pub(crate) fn solve() {
-    let data = include_str!("../../data/day_25/input.txt");
-    let puzzle = Puzzle::load(data);
-    println!("part 1: {}", solve_part_1(&puzzle));
-    println!("part 2: {}", solve_part_2(&puzzle));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,150 @@
+//! Undirected graphs, their triangles and largest clique.
+//!
+//! Nodes are kept in order, so that everything found, even among ties,
+//! does not depend on the order of the edges.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound::{Excluded, Unbounded};
+
+/// An undirected graph without loops, stored as the neighbours of each node.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Graph<N> {
+    adjacency: BTreeMap<N, BTreeSet<N>>
+}
+
+impl<N: Copy + Ord> Graph<N> {
+    /// Graph without nodes.
+    pub fn new() -> Self {
+        Self {adjacency: BTreeMap::new()}
+    }
+
+    /// Connects `a` and `b`, adding them if needed. A loop only adds the node.
+    pub fn add_edge(&mut self, a: N, b: N) {
+        self.adjacency.entry(a).or_default();
+        self.adjacency.entry(b).or_default();
+        if a != b {
+            self.adjacency.get_mut(&a).unwrap().insert(b);
+            self.adjacency.get_mut(&b).unwrap().insert(a);
+        }
+    }
+
+    /// The nodes, in order.
+    pub fn nodes(&self) -> impl Iterator<Item = N> + '_ {
+        self.adjacency.keys().copied()
+    }
+
+    /// The nodes connected to `node`, in order.
+    pub fn neighbours(&self, node: N) -> impl Iterator<Item = N> + '_ {
+        self.adjacency.get(&node).into_iter().flatten().copied()
+    }
+
+    /// Whether `a` and `b` are connected.
+    pub fn contains_edge(&self, a: N, b: N) -> bool {
+        self.adjacency.get(&a).is_some_and(|n| n.contains(&b))
+    }
+
+    /// Every set of three connected nodes, each once with its nodes in
+    /// order, sorted.
+    pub fn triangles(&self) -> Vec<[N; 3]> {
+        let mut triangles = Vec::new();
+        for (&a, next) in &self.adjacency {
+            for &b in next.range((Excluded(a), Unbounded)) {
+                for &c in self.adjacency[&b].range((Excluded(b), Unbounded)) {
+                    if next.contains(&c) {
+                        triangles.push([a, b, c]);
+                    }
+                }
+            }
+        }
+        triangles
+    }
+
+    /// A largest set of nodes all connected to each other, in order, found
+    /// by Bron–Kerbosch with pivoting. Among several, the same one for the
+    /// same nodes and edges.
+    pub fn max_clique(&self) -> Vec<N> {
+        let mut best = Vec::new();
+        let nodes = self.adjacency.keys().copied().collect();
+        self.bron_kerbosch(&mut Vec::new(), nodes, BTreeSet::new(), &mut best);
+        best.sort();
+        best
+    }
+
+    // grows `clique` with the `candidates`, the nodes already `excluded` were
+    // tried before and would give cliques already found
+    fn bron_kerbosch(&self, clique: &mut Vec<N>, mut candidates: BTreeSet<N>, mut excluded: BTreeSet<N>, best: &mut Vec<N>) {
+        if candidates.is_empty() {
+            if excluded.is_empty() && (clique.len() > best.len()) {
+                best.clone_from(clique);
+            }
+            return;
+        }
+        if clique.len() + candidates.len() <= best.len() {
+            return;
+        }
+        // any maximal clique holds the pivot or one of its non neighbours
+        let pivot = candidates.iter().chain(&excluded).copied()
+            .max_by_key(|&u| (self.adjacency[&u].intersection(&candidates).count(), std::cmp::Reverse(u)))
+            .unwrap();
+        let tried: Vec<_> = candidates.iter().copied().filter(|v| !self.contains_edge(pivot, *v)).collect();
+        for node in tried {
+            let next = &self.adjacency[&node];
+            clique.push(node);
+            self.bron_kerbosch(
+                clique,
+                candidates.intersection(next).copied().collect(),
+                excluded.intersection(next).copied().collect(),
+                best
+            );
+            clique.pop();
+            candidates.remove(&node);
+            excluded.insert(node);
+        }
+    }
+}
+
+impl<N: Copy + Ord> FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(edges: I) -> Self {
+        let mut graph = Self::new();
+        for (a, b) in edges {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph() {
+        let graph: Graph<_> = [(3, 1), (1, 2), (2, 2), (5, 5)].into_iter().collect();
+        assert_eq!(graph.nodes().collect::<Vec<_>>(), [1, 2, 3, 5]);
+        assert_eq!(graph.neighbours(1).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(graph.neighbours(4).count(), 0);
+        assert!(graph.contains_edge(2, 1) && !graph.contains_edge(2, 3) && !graph.contains_edge(2, 2));
+    }
+
+    #[test]
+    fn test_triangles() {
+        let graph: Graph<_> = [(1, 2), (2, 3), (3, 1), (3, 4), (4, 1), (4, 5)].into_iter().collect();
+        assert_eq!(graph.triangles(), [[1, 2, 3], [1, 3, 4]]);
+        assert!(Graph::<u8>::new().triangles().is_empty());
+    }
+
+    #[test]
+    fn test_max_clique() {
+        // a square with both diagonals, and a triangle hanging off it
+        let graph: Graph<_> = [(1, 2), (2, 3), (3, 4), (4, 1), (1, 3), (2, 4), (4, 5), (5, 6), (6, 4)].into_iter().collect();
+        assert_eq!(graph.max_clique(), [1, 2, 3, 4]);
+
+        // ties do not depend on the order of the edges
+        let edges = [(9, 8), (8, 7), (7, 9), (1, 2), (2, 3), (3, 1), (3, 7)];
+        assert_eq!(edges.into_iter().collect::<Graph<_>>().max_clique(), [1, 2, 3]);
+        assert_eq!(edges.into_iter().rev().collect::<Graph<_>>().max_clique(), [1, 2, 3]);
+
+        assert_eq!([(1, 1)].into_iter().collect::<Graph<_>>().max_clique(), [1]);
+        assert!(Graph::<u8>::new().max_clique().is_empty());
+    }
+}
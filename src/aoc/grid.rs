@@ -1,6 +1,97 @@
+//! Fixed size two dimensional grids, indexed by `(x, y)` with `(0, 0)` at the
+//! top left.
+
 use std::collections::VecDeque;
 use std::cmp::PartialEq;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::aoc::parse::{self, ParseError};
+use crate::aoc::search::{self, Found};
+
+/// A point, or a vector between two points, of the plane.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    /// Column, growing to the right.
+    pub x: T,
+    /// Row, growing downwards.
+    pub y: T
+}
+
+impl<T> Point<T> {
+    /// Point at `x` and `y`.
+    pub const fn new(x: T, y: T) -> Self {
+        Self {x, y}
+    }
+
+    /// Same point with other coordinates types, `None` when they do not fit.
+    pub fn cast<U: TryFrom<T>>(self) -> Option<Point<U>> {
+        Some(Point::new(U::try_from(self.x).ok()?, U::try_from(self.y).ok()?))
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Point<T> {
+    /// Manhattan distance to `other`, also fine with unsigned coordinates.
+    pub fn manhattan(self, other: Self) -> T {
+        let d = |a: T, b: T| if a > b {a - b} else {b - a};
+        d(self.x, other.x) + d(self.y, other.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(p: Point<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+    type Output = Self;
+    fn mul(self, k: T) -> Self {
+        Self::new(self.x * k, self.y * k)
+    }
+}
 
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+/// A `width` × `height` grid of cells stored row by row.
 #[derive(Clone)]
 pub struct Grid<T> {
     size: (usize, usize),
@@ -8,6 +99,7 @@ pub struct Grid<T> {
 }
 
 impl<T: Copy + PartialEq> Grid<T> {
+    /// Grid of `size` (width, height) filled with `e`, `size` must not be empty.
     pub fn new(size: (usize, usize), e: T) -> Self {
         let s = size.0 * size.1;
         assert!(s > 0);
@@ -15,7 +107,8 @@ impl<T: Copy + PartialEq> Grid<T> {
         Self {size, data}
     }
 
-    pub fn from_vec(data: &Vec<Vec<T>>) -> Self {
+    /// Grid from a list of rows, all of the same, non zero, length.
+    pub fn from_vec(data: &[Vec<T>]) -> Self {
         assert!(
             !data.is_empty() &&
             !data[0].is_empty() &&
@@ -26,18 +119,95 @@ impl<T: Copy + PartialEq> Grid<T> {
         Self {size: (width, height), data}
     }
 
+    /// Width and height.
     pub fn size(&self) -> (usize, usize) {
         self.size
     }
 
+    /// Cell at `point`, panics when outside of the grid.
     pub fn get(&self, point: (usize, usize)) -> T {
         self.data[point.1 * self.size.0 + point.0]
     }
 
+    /// Sets the cell at `point`, panics when outside of the grid.
     pub fn set(&mut self, point: (usize, usize), v: T) {
         self.data[point.1 * self.size.0 + point.0] = v;
     }
 
+    /// Position of `point` when it is inside of the grid.
+    pub fn index(&self, point: Point<i64>) -> Option<(usize, usize)> {
+        let p: Point<usize> = point.cast()?;
+        ((p.x < self.size.0) && (p.y < self.size.1)).then_some(p.into())
+    }
+
+    /// Cell at `point`, `None` when outside of the grid.
+    pub fn get_checked(&self, point: Point<i64>) -> Option<T> {
+        Some(self.get(self.index(point)?))
+    }
+
+    /// Position at `offset` from `point`, `None` when outside of the grid.
+    pub fn step(&self, point: (usize, usize), offset: Point<i64>) -> Option<(usize, usize)> {
+        self.index(Point::new(point.0 as i64, point.1 as i64) + offset)
+    }
+
+    /// Positions next to `point` in the four directions, inside of the grid.
+    pub fn neighbours4(&self, point: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL.into_iter().filter_map(move |d| self.step(point, d.offset()))
+    }
+
+    /// Positions next to `point` in the eight directions, inside of the grid.
+    pub fn neighbours8(&self, point: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction8::ALL.into_iter().filter_map(move |d| self.step(point, d.offset()))
+    }
+
+    /// Number of moves in the four directions from the nearest of `starts` to
+    /// each cell, through the cells accepted by `open`, `None` when unreachable.
+    pub fn distance_map<F: FnMut(T) -> bool>(
+        &self,
+        starts: impl IntoIterator<Item = (usize, usize)>,
+        mut open: F
+    ) -> Grid<Option<usize>> {
+        let mut distances = Grid::new(self.size, None);
+        let mut todo = VecDeque::new();
+        for start in starts {
+            if distances.get(start).is_none() && open(self.get(start)) {
+                distances.set(start, Some(0));
+                todo.push_back(start);
+            }
+        }
+        while let Some(p) = todo.pop_front() {
+            let d = distances.get(p).map(|d| d + 1);
+            for n in self.neighbours4(p) {
+                if distances.get(n).is_none() && open(self.get(n)) {
+                    distances.set(n, d);
+                    todo.push_back(n);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Shortest path from `start` to `goal` moving in the four directions
+    /// through the cells accepted by `open`, each move costs 1.
+    pub fn shortest_path<F: FnMut(T) -> bool>(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        mut open: F
+    ) -> Option<Found<(usize, usize), usize>> {
+        let distance = |p: &(usize, usize)| p.0.abs_diff(goal.0) + p.1.abs_diff(goal.1);
+        if !open(self.get(start)) {
+            return None;
+        }
+        search::astar(
+            [start],
+            |&p| self.neighbours4(p).filter(|&n| open(self.get(n))).map(|n| (n, 1)).collect::<Vec<_>>(),
+            distance,
+            |&p| p == goal
+        )
+    }
+
+    /// First cell equal to `v`, in reading order.
     pub fn find(&self, v: T) -> Option<(usize, usize)> {
         let (w, h) = self.size();
         for y in 0..h {
@@ -50,6 +220,7 @@ impl<T: Copy + PartialEq> Grid<T> {
         None
     }
 
+    /// First cell matching `f`, in reading order.
     pub fn find_by<F: FnMut(&T) -> bool>(&self, mut f: F) -> Option<(usize, usize)> {
         let (w, h) = self.size();
         for y in 0..h {
@@ -69,12 +240,14 @@ impl<T: Copy + PartialEq> Grid<T> {
     //         GridExploreIterator::new(self, start, filter)
     // }
 
-    pub fn explore<F> (&self, start: (usize, usize), filter: F) -> GridExploreIterator<T, F>
+    /// Breadth first exploration from `start`, see [`GridExploreIterator`].
+    pub fn explore<F> (&self, start: (usize, usize), filter: F) -> GridExploreIterator<'_, T, F>
     where F: FnMut((usize, usize), (usize, usize), usize) -> bool
     {
         GridExploreIterator::new(self, start, filter)
     }
 
+    /// All the cells with their position, in reading order.
     pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), T)> + '_ {
         self.data.iter().enumerate().map(|(i, c)| {
             let w = self.size.0;
@@ -84,6 +257,7 @@ impl<T: Copy + PartialEq> Grid<T> {
         })
     }
 
+    /// Cells of the row `index`, from left to right.
     pub fn row(&self, index: usize) -> impl Iterator<Item = T> + '_ {
         let mut i = 0;
         std::iter::from_fn(move || {
@@ -97,10 +271,11 @@ impl<T: Copy + PartialEq> Grid<T> {
         })
     }
 
+    /// All the rows, from top to bottom.
     pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = T> + '_> {
         let mut i = 0;
         std::iter::from_fn(move || {
-            if i < self.size().0 {
+            if i < self.size().1 {
                 let r = self.row(i);
                 i += 1;
                 Some(r)
@@ -110,6 +285,7 @@ impl<T: Copy + PartialEq> Grid<T> {
         })
     }
 
+    /// Cells of the column `index`, from top to bottom.
     pub fn column(&self, index: usize) -> impl Iterator<Item = T> + '_ {
         let mut i = 0;
         std::iter::from_fn(move || {
@@ -123,6 +299,7 @@ impl<T: Copy + PartialEq> Grid<T> {
         })
     }
 
+    /// All the columns, from left to right.
     pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = T> + '_> {
         let mut i = 0;
         std::iter::from_fn(move || {
@@ -138,63 +315,198 @@ impl<T: Copy + PartialEq> Grid<T> {
 }
 
 impl<T: Default + Copy + PartialEq> Grid<T> {
+    /// Grid of `size` filled with the default value.
     pub fn new_default(size: (usize, usize)) -> Self {
         Self::new(size, Default::default())
     }
 }
 
 impl<T: Copy + PartialEq + std::str::FromStr + std::fmt::Debug> Grid<T> {
-    pub fn parse(data: &str, sep: &str) -> Option<Self> where <T as std::str::FromStr>::Err: std::fmt::Debug {
-        let data: Result<Vec<Vec<_>>, _> = data.trim().lines().map(|r| {
+    /// Parses one row per line, cells are separated by `sep` or are single
+    /// characters when `sep` is empty.
+    pub fn parse(data: &str, sep: &str) -> Result<Self, ParseError> where <T as std::str::FromStr>::Err: std::fmt::Debug {
+        let lines: Vec<_> = data.trim().lines().map(|r| r.trim()).collect();
+        let rows: Result<Vec<Vec<_>>, _> = lines.iter().map(|r| {
             if sep.is_empty() {
-                r.trim().chars().map(|c| T::from_str(&c.to_string())).collect()
+                r.char_indices().map(|(i, c)| parse::value(data, &r[i..(i + c.len_utf8())], "a grid cell")).collect()
             } else {
-                r.trim().split(sep).map(|s| T::from_str(&s)).collect()
+                r.split(sep).map(|s| parse::value(data, s, "a grid cell")).collect()
             }
         }).collect();
-        let data = data.ok()?;
-        if !data.is_empty() && !data[0].is_empty() && data.iter().skip(1).all(|r| r.len() == data[0].len()) {
-            Some(Self::from_vec(&data))
-        } else {
-            None
+        let rows = rows?;
+        if rows.is_empty() || rows[0].is_empty() {
+            return Err(ParseError::at(data, data.trim(), "a grid"));
+        }
+        if let Some(r) = rows.iter().position(|r| r.len() != rows[0].len()) {
+            return Err(ParseError::at(data, lines[r], &format!("a row of {} cells", rows[0].len())));
         }
+        Ok(Self::from_vec(&rows))
     }
 
+    /// Same as [`Grid::parse`], panics on invalid input.
     pub fn load(data: &str, sep: &str) -> Self where <T as std::str::FromStr>::Err: std::fmt::Debug {
-        Self::parse(data, sep).expect("valid input")
+        Self::parse(data, sep).unwrap_or_else(|e| panic!("invalid input at {e}"))
     }
 }
 
-enum Dir {
+/// One of the four directions, north is up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    /// Up, `^`.
+    North,
+    /// Right, `>`.
     East,
-    West,
+    /// Down, `v`.
+    South,
+    /// Left, `<`.
+    West
+}
+
+impl Direction {
+    /// All the directions, clockwise from north.
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    /// Offset of a step in this direction.
+    pub fn offset(self) -> Point<i64> {
+        match self {
+            Direction::North => Point::new(0, -1),
+            Direction::East => Point::new(1, 0),
+            Direction::South => Point::new(0, 1),
+            Direction::West => Point::new(-1, 0)
+        }
+    }
+
+    /// Direction after a quarter turn counterclockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    /// Direction after a quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    /// Direction after a half turn.
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Whether this is east or west.
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Direction::East | Direction::West)
+    }
+}
+
+/// Parses an arrow, `^`, `>`, `v` or `<`, the error is the rejected character.
+impl TryFrom<char> for Direction {
+    type Error = char;
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' => Ok(Direction::North),
+            '>' => Ok(Direction::East),
+            'v' => Ok(Direction::South),
+            '<' => Ok(Direction::West),
+            c => Err(c)
+        }
+    }
+}
+
+/// The arrow of a direction, `^`, `>`, `v` or `<`.
+impl From<Direction> for char {
+    fn from(d: Direction) -> Self {
+        match d {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<'
+        }
+    }
+}
+
+/// One of the eight directions, north is up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    /// Up.
     North,
-    South
+    /// Up and right.
+    NorthEast,
+    /// Right.
+    East,
+    /// Down and right.
+    SouthEast,
+    /// Down.
+    South,
+    /// Down and left.
+    SouthWest,
+    /// Left.
+    West,
+    /// Up and left.
+    NorthWest
 }
 
-impl Dir {
-    fn offset(&self) -> (i32, i32) {
+impl Direction8 {
+    /// All the directions, clockwise from north.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North, Direction8::NorthEast, Direction8::East, Direction8::SouthEast,
+        Direction8::South, Direction8::SouthWest, Direction8::West, Direction8::NorthWest
+    ];
+
+    /// Offset of a step in this direction.
+    pub fn offset(self) -> Point<i64> {
         match self {
-            Dir::East => (-1, 0),
-            Dir::West => (1, 0),
-            Dir::North => (0, -1),
-            Dir::South => (0, 1)
+            Direction8::North => Point::new(0, -1),
+            Direction8::NorthEast => Point::new(1, -1),
+            Direction8::East => Point::new(1, 0),
+            Direction8::SouthEast => Point::new(1, 1),
+            Direction8::South => Point::new(0, 1),
+            Direction8::SouthWest => Point::new(-1, 1),
+            Direction8::West => Point::new(-1, 0),
+            Direction8::NorthWest => Point::new(-1, -1)
         }
     }
 
-    fn all() -> [Dir; 4] {
-        [Dir::East, Dir::West, Dir::North, Dir::South]
+    /// Direction after an eighth of a turn counterclockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    /// Direction after an eighth of a turn clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Direction after a half turn.
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
     }
 }
 
+impl From<Direction> for Direction8 {
+    fn from(d: Direction) -> Self {
+        match d {
+            Direction::North => Direction8::North,
+            Direction::East => Direction8::East,
+            Direction::South => Direction8::South,
+            Direction::West => Direction8::West
+        }
+    }
+}
+
+type GridExploreItem = ((usize, usize), (usize, usize), usize);
+
+/// Breadth first exploration of a grid.
+///
+/// Yields `(position, previous position, distance)` for each reachable cell,
+/// each cell is visited once and only when `filter` accepts the same triple.
 pub struct GridExploreIterator<'a, T, F: FnMut((usize, usize), (usize, usize), usize) -> bool> {
     grid: &'a Grid<T>,
     filter: F,
-    positions: VecDeque<((usize, usize), (usize, usize), usize)>,
+    positions: VecDeque<GridExploreItem>,
     visited: Vec<u64>
 }
 
 impl<'a, T: Copy + PartialEq, F: FnMut((usize, usize), (usize, usize), usize) -> bool> GridExploreIterator<'a, T, F> {
+    /// Exploration of `grid` from `start`.
     pub fn new(grid: &'a Grid<T>, start: (usize, usize), filter: F) -> Self {
         let (w, h) = grid.size();
         let positions = VecDeque::new();
@@ -206,7 +518,7 @@ impl<'a, T: Copy + PartialEq, F: FnMut((usize, usize), (usize, usize), usize) ->
 
     fn visit(&mut self, position: (usize, usize), pposition: (usize, usize), distance: usize) {
         let (w, _) = self.grid.size();
-        let w64 = (w + 63) / 64;
+        let w64 = w.div_ceil(64);
         let (vx, vy) = (position.0 / 64, position.1);
         let bx = 1 << (position.0 % 64);
         let v = vy * w64 + vx;
@@ -220,19 +532,13 @@ impl<'a, T: Copy + PartialEq, F: FnMut((usize, usize), (usize, usize), usize) ->
 }
 
 impl<'a, T: Copy + PartialEq, F: FnMut((usize, usize), (usize, usize), usize) -> bool> Iterator for GridExploreIterator<'a, T, F> {
-    type Item = ((usize, usize), (usize, usize), usize);
+    type Item = GridExploreItem;
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some((position, pposition, distance)) = self.positions.pop_front() {
-            let (w, h) = {let (w, h) = self.grid.size(); (w as i32, h as i32)};
-            let (x, y) = (position.0 as i32, position.1 as i32);
-            for d in Dir::all() {
-                let (dx, dy) = d.offset();
-                let (x, y) = (x + dx, y + dy);
-                if (x >= 0) && (x < w) && (y >= 0) && (y < h) {
-                    let p = (x as usize, y as usize);
-                    self.visit(p, position, distance + 1);
-                }
+            let grid = self.grid;
+            for p in grid.neighbours4(position) {
+                self.visit(p, position, distance + 1);
             }
             Some((position, pposition, distance))
         } else {
@@ -242,13 +548,9 @@ impl<'a, T: Copy + PartialEq, F: FnMut((usize, usize), (usize, usize), usize) ->
 }
 
 impl std::str::FromStr for Grid<char> {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let grid: Option<Self> = Grid::parse(s, "");
-        match grid {
-            Some(grid) => Ok(grid),
-            None => Err(())
-        }
+        Grid::parse(s, "")
     }
 }
 
@@ -257,7 +559,7 @@ impl std::fmt::Display for Grid<char> {
         let mut nl = false;
         for y in 0..(self.size.1) {
             if nl {
-                writeln!(f, "")?;
+                writeln!(f)?;
             }
             for x in 0..(self.size.0) {
                 write!(f, "{}", self.get((x, y)))?;
@@ -288,6 +590,66 @@ impl std::fmt::Display for Grid<char> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_point() {
+        let (p, q) = (Point::new(1i64, 2), Point::new(4, -2));
+        assert_eq!(p + q, Point::new(5, 0));
+        assert_eq!(p - q, Point::new(-3, 4));
+        assert_eq!(-p, Point::new(-1, -2));
+        assert_eq!(q * 3, Point::new(12, -6));
+        assert_eq!(p.manhattan(q), 7);
+        assert_eq!(Point::new(3usize, 5).manhattan(Point::new(5, 1)), 6);
+        assert_eq!(q.cast::<usize>(), None);
+        assert_eq!(p.cast::<usize>(), Some(Point::new(1, 2)));
+        let mut r = p;
+        r += q;
+        r -= p;
+        assert_eq!(r, q);
+        assert_eq!(<(i64, i64)>::from(p), (1, 2));
+    }
+
+    #[test]
+    fn test_get_checked() {
+        let grid: Grid<u8> = Grid::load("12\n34\n56", "");
+        assert_eq!(grid.get_checked(Point::new(1, 2)), Some(6));
+        assert_eq!(grid.get_checked(Point::new(2, 0)), None);
+        assert_eq!(grid.get_checked(Point::new(0, 3)), None);
+        assert_eq!(grid.get_checked(Point::new(-1, 0)), None);
+        assert_eq!(grid.index(Point::new(1, 1)), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::East.offset(), Point::new(1, 0));
+        assert_eq!(Direction::West.offset(), Point::new(-1, 0));
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::South.opposite(), Direction::North);
+        for d in Direction::ALL {
+            assert_eq!(d.offset() + d.opposite().offset(), Point::new(0, 0));
+            assert_eq!(Direction::try_from(char::from(d)), Ok(d));
+            assert_eq!(Direction8::from(d).offset(), d.offset());
+        }
+        assert_eq!(Direction::try_from('x'), Err('x'));
+
+        assert_eq!(Direction8::NorthEast.offset(), Point::new(1, -1));
+        assert_eq!(Direction8::North.turn_right(), Direction8::NorthEast);
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::SouthWest.opposite(), Direction8::NorthEast);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid: Grid<u8> = Grid::load("123\n456\n789", "");
+        let ns: Vec<_> = grid.neighbours4((0, 0)).map(|p| grid.get(p)).collect();
+        assert_eq!(ns, [2, 4]);
+        let ns: Vec<_> = grid.neighbours4((1, 1)).map(|p| grid.get(p)).collect();
+        assert_eq!(ns, [2, 6, 8, 4]);
+        let ns: Vec<_> = grid.neighbours8((2, 0)).map(|p| grid.get(p)).collect();
+        assert_eq!(ns, [6, 5, 2]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    }
+
     #[test]
     fn test_parse() {
         let data = "
@@ -321,8 +683,15 @@ mod tests {
         1X3
         456
         ";
-        let grid: Option<Grid<u8>> = Grid::parse(data, "");
-        assert!(grid.is_none());
+        let grid: Result<Grid<u8>, _> = Grid::parse(data, "");
+        assert_eq!(grid.err().map(|e| (e.line, e.column, e.text)), Some((2, 10, "X".to_string())));
+
+        let data = "
+        123
+        45
+        ";
+        let grid: Result<Grid<u8>, _> = Grid::parse(data, "");
+        assert_eq!(grid.err().map(|e| (e.line, e.column, e.expected)), Some((3, 9, "a row of 3 cells".to_string())));
     }
 
     #[test]
@@ -337,6 +706,37 @@ mod tests {
         assert_eq!(grid.explore((2, 1), |p, _, _| grid.get(p) != '#').count(), 3);
     }
 
+    #[test]
+    fn test_distance_map() {
+        let data = "
+        #.###
+        #...#
+        ###.#
+        ";
+        let grid: Grid<char> = Grid::load(data, "");
+        let distances = grid.distance_map([(1, 0)], |c| c != '#');
+        assert_eq!(distances.row(1).collect::<Vec<_>>(), [None, Some(1), Some(2), Some(3), None]);
+        assert_eq!(distances.get((3, 2)), Some(4));
+        let distances = grid.distance_map([(1, 0), (3, 2)], |c| c != '#');
+        assert_eq!(distances.row(1).collect::<Vec<_>>(), [None, Some(1), Some(2), Some(1), None]);
+        let distances = grid.distance_map([(0, 0)], |c| c != '#');
+        assert!(distances.cells().all(|(_, d)| d.is_none()));
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let data = "
+        #.###
+        #...#
+        ###.#
+        ";
+        let grid: Grid<char> = Grid::load(data, "");
+        let found = grid.shortest_path((1, 0), (3, 2), |c| c != '#').unwrap();
+        assert_eq!(found.cost, 4);
+        assert_eq!(found.path, [(1, 0), (1, 1), (2, 1), (3, 1), (3, 2)]);
+        assert!(grid.shortest_path((1, 0), (0, 0), |c| c != '#').is_none());
+    }
+
     #[test]
     fn test_cells() {
         let data = "
@@ -389,6 +789,23 @@ mod tests {
         assert_eq!(rows.next().unwrap().collect::<Vec<_>>(), vec!['1', '2']);
         assert_eq!(rows.next().unwrap().collect::<Vec<_>>(), vec!['3', '4']);
         assert!(rows.next().is_none());
+
+        // as many rows as the height, not the width
+        let data = "
+        123
+        456
+        ";
+        let grid: Grid<char> = Grid::load(data, "");
+        let rows: Vec<String> = grid.rows().map(|r| r.collect()).collect();
+        assert_eq!(rows, ["123", "456"]);
+        let data = "
+        12
+        34
+        56
+        ";
+        let grid: Grid<char> = Grid::load(data, "");
+        let rows: Vec<String> = grid.rows().map(|r| r.collect()).collect();
+        assert_eq!(rows, ["12", "34", "56"]);
     }
 
     #[test]
@@ -1,7 +1,20 @@
-use crate::aoc::grid::{Grid, GridExploreIterator};
+//! Mazes drawn with characters, walls and open cells.
 
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use crate::aoc::grid::{Grid, GridExploreIterator, Point};
+use crate::aoc::parse::ParseError;
+use crate::aoc::search::{self, Paths};
+
+/// Breadth first exploration of a maze, see [`GridExploreIterator`].
 pub type MazeExploreIterator<'a, F>  = GridExploreIterator<'a, char, F>;
 
+/// Filter of a [`MazeExploreIterator`].
+pub trait MazeExploreFilter: FnMut((usize, usize), (usize, usize), usize) -> bool {}
+
+impl<F: FnMut((usize, usize), (usize, usize), usize) -> bool> MazeExploreFilter for F {}
+
+/// Positions of a path, from its beginning to its end.
 pub struct MazePathIterator {
     path: Vec<(usize, usize)>
 }
@@ -14,38 +27,109 @@ impl Iterator for MazePathIterator {
     }
 }
 
+/// Every shortest path of a maze, see [`Maze::get_paths`].
+pub type MazePaths = Paths<(usize, usize), usize>;
+
+/// What blocks movement in a maze: a wall character, a set of them, or a
+/// predicate telling which characters can be walked on.
+pub trait Walls {
+    /// Whether `c` can be walked on.
+    fn is_open(&self, c: char) -> bool;
+}
+
+impl Walls for char {
+    fn is_open(&self, c: char) -> bool {
+        c != *self
+    }
+}
+
+impl Walls for &[char] {
+    fn is_open(&self, c: char) -> bool {
+        !self.contains(&c)
+    }
+}
+
+impl<const N: usize> Walls for [char; N] {
+    fn is_open(&self, c: char) -> bool {
+        !self.contains(&c)
+    }
+}
+
+impl Walls for &str {
+    fn is_open(&self, c: char) -> bool {
+        !self.contains(c)
+    }
+}
+
+impl<F: Fn(char) -> bool> Walls for F {
+    fn is_open(&self, c: char) -> bool {
+        self(c)
+    }
+}
+
+/// How a cheat moves, see [`CheatRules`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheatMetric {
+    /// Straight to any cell within the radius, in Manhattan distance.
+    Manhattan,
+    /// Step by step through walls only, up to the first open cell.
+    ThroughWalls
+}
+
+/// A race from `start` to `end` where one cheat may go through walls, see
+/// [`Maze::cheats`].
+#[derive(Clone, Debug)]
+pub struct CheatRules<W: Walls> {
+    /// Where the race starts.
+    pub start: (usize, usize),
+    /// Where the race ends.
+    pub end: (usize, usize),
+    /// The walls.
+    pub walls: W,
+    /// Longest cheat, in moves.
+    pub radius: usize,
+    /// How the cheat moves.
+    pub metric: CheatMetric
+}
+
+/// A maze, the walls are given to each query, see [`Walls`].
 pub struct Maze {
     map: Grid<char>
 }
 
 impl Maze {
-    pub fn parse(data: &str) -> Option<Self> {
-        Some(Self {map: data.parse().ok()?})
+    /// Parses one row of characters per line.
+    pub fn parse(data: &str) -> Result<Self, ParseError> {
+        Ok(Self {map: data.parse()?})
     }
 
+    /// Same as [`Maze::parse`], panics on invalid input.
     pub fn load(data: &str) -> Self {
-        Self::parse(data).expect("valid input")
+        Self::parse(data).unwrap_or_else(|e| panic!("invalid input at {e}"))
     }
 
+    /// The underlying grid.
     pub fn get_map(&self) -> &Grid<char> {
         &self.map
     }
 
-    pub fn explore(
-        &self,
+    /// Breadth first exploration from `start`, avoiding `walls`.
+    pub fn explore<'a>(
+        &'a self,
         start: (usize, usize),
-        wall: char
-    ) -> MazeExploreIterator<impl FnMut((usize, usize), (usize, usize), usize) -> bool + '_> {
-        MazeExploreIterator::new(self.get_map(), start, move |p, _, _| self.get_map().get(p) != wall)
+        walls: impl Walls + 'a
+    ) -> MazeExploreIterator<'a, impl MazeExploreFilter + 'a> {
+        MazeExploreIterator::new(self.get_map(), start, move |p, _, _| walls.is_open(self.get_map().get(p)))
     }
 
+    /// A shortest path from `begin` to `end`, both included.
     pub fn get_path(
         &self, begin: (usize, usize),
         end: (usize, usize),
-        wall: char
+        walls: impl Walls
     ) -> Option<MazePathIterator> {
         let mut pps:Grid<(usize, usize)> = Grid::new(self.map.size(), (0, 0));
-        for (p, pp, _) in self.explore(begin, wall) {
+        for (p, pp, _) in self.explore(begin, walls) {
             pps.set(p, pp);
             if p == end {
                 let mut path = Vec::new();
@@ -63,13 +147,111 @@ impl Maze {
         None
     }
 
+    /// Every shortest path from `begin` to `end`, with all the cells before
+    /// each cell on them.
+    pub fn get_paths(
+        &self,
+        begin: (usize, usize),
+        end: (usize, usize),
+        walls: impl Walls
+    ) -> Option<MazePaths> {
+        if !walls.is_open(self.map.get(begin)) {
+            return None;
+        }
+        let open = |&p: &(usize, usize)| walls.is_open(self.map.get(p));
+        search::dijkstra_all(
+            [begin],
+            |&p| self.map.neighbours4(p).filter(open).map(|n| (n, 1)).collect::<Vec<_>>(),
+            |&p| p == end
+        )
+    }
+
+    /// Length of a shortest path from `start` to each cell, avoiding `walls`,
+    /// `None` when unreachable.
+    pub fn distance_map(&self, start: (usize, usize), walls: impl Walls) -> Grid<Option<usize>> {
+        self.distance_map_from([start], walls)
+    }
+
+    /// Same as [`Maze::distance_map`] from the nearest of `starts`.
+    pub fn distance_map_from(
+        &self,
+        starts: impl IntoIterator<Item = (usize, usize)>,
+        walls: impl Walls
+    ) -> Grid<Option<usize>> {
+        self.map.distance_map(starts, |c| walls.is_open(c))
+    }
+
+    /// Histogram of the cheats saving time, the number of cheats by time
+    /// saved, `None` when the race cannot be finished without cheating.
+    ///
+    /// A cheat is identified by where it starts and where it ends, it must
+    /// end on an open cell.
+    pub fn cheats(&self, rules: &CheatRules<impl Walls>) -> Option<BTreeMap<usize, usize>> {
+        let open = |c: char| rules.walls.is_open(c);
+        let from_start = self.map.distance_map([rules.start], open);
+        let to_end = self.map.distance_map([rules.end], open);
+        let distance = from_start.get(rules.end)?;
+        let mut histogram = BTreeMap::new();
+        for (p, d) in from_start.cells() {
+            let Some(d) = d else {continue};
+            for (cp, cd) in self.cheat_ends(p, rules) {
+                if let Some(de) = to_end.get(cp) {
+                    let d = d + cd + de;
+                    if distance > d {
+                        *histogram.entry(distance - d).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        Some(histogram)
+    }
+
+    // cells a cheat from `p` can end on, with the length of the cheat
+    fn cheat_ends(&self, p: (usize, usize), rules: &CheatRules<impl Walls>) -> Vec<((usize, usize), usize)> {
+        match rules.metric {
+            CheatMetric::Manhattan => {
+                let r = rules.radius as i64;
+                let p = Point::new(p.0 as i64, p.1 as i64);
+                (-r..=r).flat_map(|y| {
+                    let r = r - y.abs();
+                    (-r..=r).map(move |x| Point::new(x, y))
+                }).filter_map(|o| {
+                    let cd = o.manhattan(Point::default()) as usize;
+                    Some((self.map.index(p + o)?, cd)).filter(|_| cd > 0)
+                }).collect()
+            }
+            CheatMetric::ThroughWalls => {
+                let mut ends = Vec::new();
+                let mut visited = HashSet::from([p]);
+                let mut todo = VecDeque::from([(p, 0)]);
+                while let Some((p, d)) = todo.pop_front() {
+                    if d == rules.radius {
+                        continue;
+                    }
+                    for n in self.map.neighbours4(p) {
+                        if visited.insert(n) {
+                            if !rules.walls.is_open(self.map.get(n)) {
+                                todo.push_back((n, d + 1));
+                            } else {
+                                ends.push((n, d + 1));
+                            }
+                        }
+                    }
+                }
+                ends
+            }
+        }
+    }
+
+    /// Length of a shortest path from `begin` to `end`, see
+    /// [`Maze::distance_map`] for many queries from the same cell.
     pub fn get_distance(
         &self,
         begin: (usize, usize),
         end: (usize, usize),
-        wall: char
+        walls: impl Walls
     ) -> Option<usize> {
-        for (p, _, d) in self.explore(begin, wall) {
+        for (p, _, d) in self.explore(begin, walls) {
             if p == end {
                 return Some(d);
             }
@@ -79,13 +261,9 @@ impl Maze {
 }
 
 impl std::str::FromStr for Maze {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let map = s.parse();
-        match map {
-            Ok(map) => Ok(Maze {map}),
-            Err(_) => Err(())
-        }
+        Ok(Maze {map: s.parse()?})
     }
 }
 
@@ -116,6 +294,27 @@ mod tests {
         assert_eq!(maze.explore((2, 1), '.').count(), 0);
     }
 
+    #[test]
+    fn test_walls() {
+        let data = "
+        #######
+        #B.O.E#
+        #.###.#
+        #.....#
+        #######
+        ";
+        let maze = Maze::load(data);
+        let (begin, end) = ((1, 1), (5, 1));
+        assert_eq!(maze.get_distance(begin, end, '#'), Some(4));
+        assert_eq!(maze.get_distance(begin, end, ['#', 'O']), Some(8));
+        assert_eq!(maze.get_distance(begin, end, "#O"), Some(8));
+        assert_eq!(maze.get_distance(begin, end, &['#', 'O'][..]), Some(8));
+        assert_eq!(maze.get_distance(begin, end, |c| (c == 'B') || (c == '.') || (c == 'E')), Some(8));
+        assert_eq!(maze.get_distance(begin, end, |c: char| c != '#'), Some(4));
+        assert_eq!(maze.explore(begin, |c| c == 'B').count(), 1);
+        assert_eq!(maze.get_paths(begin, end, "#O.").map(|p| p.count()), None);
+    }
+
     #[test]
     fn test_get_distance() {
         let data = "
@@ -153,6 +352,60 @@ mod tests {
         assert_eq!(maze.get_distance(begin, end, '#'), Some(10));
     }
 
+    #[test]
+    fn test_distance_map() {
+        let data = "
+        #######
+        #B#.#E#
+        #.....#
+        #######
+        ";
+        let maze = Maze::load(data);
+        let (begin, end) = (maze.get_map().find('B').unwrap(), maze.get_map().find('E').unwrap());
+        let distances = maze.distance_map(begin, '#');
+        assert_eq!(distances.get(end), Some(6));
+        assert_eq!(distances.get((3, 1)), Some(4));
+        assert_eq!(distances.get((2, 1)), None);
+        for (p, d) in distances.cells() {
+            assert_eq!(d, maze.get_distance(begin, p, '#'));
+        }
+
+        let distances = maze.distance_map_from([begin, end], '#');
+        assert_eq!(distances.get((2, 2)), Some(2));
+        assert_eq!(distances.get((4, 2)), Some(2));
+        assert_eq!(distances.get((3, 1)), Some(4));
+    }
+
+    #[test]
+    fn test_cheats() {
+        let data = "
+        #######
+        #S#...#
+        #.#.#.#
+        #...#E#
+        #######
+        ";
+        let maze = Maze::load(data);
+        let (start, end) = (maze.get_map().find('S').unwrap(), maze.get_map().find('E').unwrap());
+        let mut rules = CheatRules {start, end, walls: '#', radius: 2, metric: CheatMetric::Manhattan};
+        let histogram = maze.cheats(&rules).unwrap();
+        assert_eq!(histogram.into_iter().collect::<Vec<_>>(), [(2, 2), (4, 2)]);
+        rules.metric = CheatMetric::ThroughWalls;
+        let histogram = maze.cheats(&rules).unwrap();
+        assert_eq!(histogram.into_iter().collect::<Vec<_>>(), [(2, 2), (4, 2)]);
+
+        // (1, 1) to (4, 1) and (2, 3) to (5, 3) go through open cells
+        rules.radius = 3;
+        let histogram = maze.cheats(&rules).unwrap();
+        assert_eq!(histogram.into_iter().collect::<Vec<_>>(), [(2, 6), (4, 2)]);
+        rules.metric = CheatMetric::Manhattan;
+        let histogram = maze.cheats(&rules).unwrap();
+        assert_eq!(histogram.into_iter().collect::<Vec<_>>(), [(2, 6), (4, 4)]);
+
+        let rules = CheatRules {start, end, walls: ['#', '.'], radius: 3, metric: CheatMetric::Manhattan};
+        assert!(maze.cheats(&rules).is_none());
+    }
+
     #[test]
     fn test_get_path() {
         let data = "
@@ -167,4 +420,29 @@ mod tests {
         assert_eq!(path.next(), Some((2, 1)));
         assert_eq!(path.next(), None);
     }
+
+    #[test]
+    fn test_get_paths() {
+        let data = "
+        #######
+        #B....#
+        #.#.#.#
+        #....E#
+        #######
+        ";
+        let maze = Maze::load(data);
+        let map = &maze.get_map();
+        let paths = maze.get_paths(map.find('B').unwrap(), map.find('E').unwrap(), '#').unwrap();
+        assert_eq!(paths.cost(), 6);
+        assert_eq!(paths.count(), 3);
+        assert_eq!(paths.states().len(), 13);
+        let mut routes: Vec<_> = paths.iter().collect();
+        routes.sort();
+        routes.dedup();
+        assert_eq!(routes.len(), 3);
+        assert!(routes.iter().all(|r| (r.len() == 7) && (r[0] == (1, 1)) && (r[6] == (5, 3))));
+
+        assert!(maze.get_paths((1, 1), (5, 3), 'B').is_none());
+        assert!(maze.get_paths((1, 1), (0, 0), '#').is_none());
+    }
 }
@@ -0,0 +1,127 @@
+//! Parse errors locating the offending part of an input, and helpers to
+//! build them while parsing slices of that input.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Why and where an input could not be parsed.
+///
+/// `line` and `column` start at 1, `text` is the offending part of the input
+/// (only its first line), it is empty when something is missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// Line of the error.
+    pub line: usize,
+    /// Column of the error, in characters.
+    pub column: usize,
+    /// The offending text.
+    pub text: String,
+    /// What was expected instead.
+    pub expected: String
+}
+
+impl ParseError {
+    /// Error at `line` and `column`.
+    pub fn new(line: usize, column: usize, text: &str, expected: &str) -> Self {
+        let text = text.lines().next().unwrap_or("").to_string();
+        Self {line, column, text, expected: expected.to_string()}
+    }
+
+    /// Error on `text`, which must be a slice of `data`.
+    pub fn at(data: &str, text: &str, expected: &str) -> Self {
+        let offset = (text.as_ptr() as usize).wrapping_sub(data.as_ptr() as usize);
+        debug_assert!(offset <= data.len(), "text is not a slice of data");
+        let before = &data[..offset.min(data.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        Self::new(line, column, text, expected)
+    }
+
+    /// Error right after `text`, a slice of `data`, for something missing.
+    pub fn after(data: &str, text: &str, expected: &str) -> Self {
+        Self::at(data, &text[text.len()..], expected)
+    }
+
+    /// Moves an error found while parsing `text` to its position in `data`.
+    pub fn within(self, data: &str, text: &str) -> Self {
+        let start = Self::at(data, text, "");
+        let column = if self.line == 1 {start.column + self.column - 1} else {self.column};
+        Self {line: start.line + self.line - 1, column, ..self}
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: expected {}", self.line, self.column, self.expected)?;
+        if self.text.is_empty() {
+            write!(f, ", found nothing")
+        } else {
+            write!(f, ", found '{}'", self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `text`, a slice of `data`.
+pub fn value<T: FromStr>(data: &str, text: &str, expected: &str) -> Result<T, ParseError> {
+    text.parse().map_err(|_| ParseError::at(data, text, expected))
+}
+
+/// Splits `text`, a slice of `data`, around the first `sep`.
+pub fn split_once<'a>(data: &str, text: &'a str, sep: &str) -> Result<(&'a str, &'a str), ParseError> {
+    text.split_once(sep).ok_or_else(|| ParseError::at(data, text, &format!("'{sep}'")))
+}
+
+/// Next of the `items` split from `text`, a slice of `data`.
+pub fn next<'a>(
+    data: &str,
+    text: &str,
+    items: &mut impl Iterator<Item = &'a str>,
+    expected: &str
+) -> Result<&'a str, ParseError> {
+    items.next().ok_or_else(|| ParseError::after(data, text, expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at() {
+        let data = "12 34\n56 x8\n";
+        let e = ParseError::at(data, &data[9..11], "a number");
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 4, "x8"));
+        assert_eq!(e.to_string(), "line 2, column 4: expected a number, found 'x8'");
+
+        let e = ParseError::after(data, &data[..5], "a number");
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 6, ""));
+        assert_eq!(e.to_string(), "line 1, column 6: expected a number, found nothing");
+
+        let e = ParseError::at(data, data, "a grid");
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 1, "12 34"));
+    }
+
+    #[test]
+    fn test_within() {
+        let data = "abc\n\nde\nfgh";
+        let block = &data[5..];
+        let e = ParseError::at(block, &block[1..2], "x").within(data, block);
+        assert_eq!((e.line, e.column), (3, 2));
+        let e = ParseError::at(block, &block[4..5], "x").within(data, block);
+        assert_eq!((e.line, e.column), (4, 2));
+    }
+
+    #[test]
+    fn test_helpers() {
+        let data = "a: 1, b";
+        assert_eq!(split_once(data, data, ": "), Ok(("a", "1, b")));
+        assert_eq!(value::<u32>(data, &data[3..4], "a number"), Ok(1));
+        let e = value::<u32>(data, &data[6..], "a number").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 7));
+        let mut items = data.split(", ");
+        assert_eq!(next(data, data, &mut items, "an item"), Ok("a: 1"));
+        assert_eq!(next(data, data, &mut items, "an item"), Ok("b"));
+        assert_eq!(next(data, data, &mut items, "an item").unwrap_err().column, 8);
+    }
+}
@@ -0,0 +1,180 @@
+//! Timed runs of the days, with failures caught and reported.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use crate::aoc::{Answer, Day, Solution};
+
+/// Outcome of a part.
+pub enum Status {
+    /// The answer found.
+    Ok(String),
+    /// The part has no solver yet.
+    Unimplemented,
+    /// The solver failed or panicked, with its message.
+    Error(String)
+}
+
+/// Outcome of the comparison with a known answer.
+pub enum Check {
+    /// The answer is the known one.
+    Correct,
+    /// The answer differs, with the expected one.
+    Wrong(String)
+}
+
+/// Run of a part.
+pub struct Part {
+    /// What the part answered.
+    pub status: Status,
+    /// Time taken by the part.
+    pub duration: Duration,
+    /// Comparison with the known answer, if any, see [`Report::check`].
+    pub check: Option<Check>
+}
+
+/// Run of a day.
+pub struct Report {
+    /// The day run.
+    pub day: &'static Day,
+    /// Time taken to parse the input, or why it failed.
+    pub parse: Result<Duration, String>,
+    /// Runs of part 1 and part 2, `None` when not selected or not run.
+    pub parts: [Option<Part>; 2]
+}
+
+impl Report {
+    /// Time taken by the parse and the parts.
+    pub fn total(&self) -> Duration {
+        let parse = *self.parse.as_ref().unwrap_or(&Duration::ZERO);
+        parse + self.parts.iter().flatten().map(|p| p.duration).sum::<Duration>()
+    }
+
+    /// Whether the input was parsed and no part failed.
+    pub fn is_ok(&self) -> bool {
+        self.parse.is_ok() && self.parts.iter().flatten().all(|p| !matches!(p.status, Status::Error(_)))
+    }
+
+    /// Whether no part answered differently from a known answer.
+    pub fn is_correct(&self) -> bool {
+        self.parts.iter().flatten().all(|p| !matches!(p.check, Some(Check::Wrong(_))))
+    }
+
+    /// Compares the answers found against the expected ones, parts without an
+    /// expected answer or without an answer are left unchecked.
+    pub fn check(&mut self, expected: &Answers) {
+        for (part, expected) in self.parts.iter_mut().zip(&expected.0) {
+            if let (Some(part), Some(expected)) = (part, expected) {
+                if let Status::Ok(answer) = &part.status {
+                    part.check = Some(if answer == expected {Check::Correct} else {Check::Wrong(expected.clone())});
+                }
+            }
+        }
+    }
+}
+
+/// Known answers of a day, stored as `part 1: <answer>` and `part 2: <answer>`
+/// lines, the same format the runner prints.
+pub struct Answers([Option<String>; 2]);
+
+impl Answers {
+    /// Parses the lines of an answers file, `None` when a line is invalid.
+    pub fn parse(data: &str) -> Option<Self> {
+        let mut answers = [None, None];
+        for line in data.trim().lines() {
+            let (part, answer) = line.trim().split_once(": ")?;
+            let part = match part {
+                "part 1" => 0,
+                "part 2" => 1,
+                _ => return None
+            };
+            answers[part] = Some(answer.trim().to_string());
+        }
+        Some(Self(answers))
+    }
+
+    /// Known answer of `part` (1 or 2).
+    pub fn get(&self, part: u8) -> Option<&str> {
+        self.0.get(usize::from(part).checked_sub(1)?)?.as_deref()
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Result<T, String>, Duration) {
+    let start = Instant::now();
+    let r = panic::catch_unwind(AssertUnwindSafe(f));
+    let duration = start.elapsed();
+    let r = r.map_err(|e| {
+        if let Some(s) = e.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = e.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "panicked".to_string()
+        }
+    });
+    (r, duration)
+}
+
+fn run_part(puzzle: &dyn Solution, part: fn(&dyn Solution) -> Result<Option<Answer>, String>) -> Part {
+    let (answer, duration) = timed(|| part(puzzle).map(|a| a.map(|a| a.to_string())));
+    let status = match answer.and_then(|a| a) {
+        Ok(Some(answer)) => Status::Ok(answer),
+        Ok(None) => Status::Unimplemented,
+        Err(e) => Status::Error(e)
+    };
+    Part {status, duration, check: None}
+}
+
+/// Parses `data` for `day` and runs the selected part, or both when `part` is `None`.
+pub fn run(day: &'static Day, data: &str, part: Option<u8>) -> Report {
+    let (puzzle, parse) = timed(|| day.parse(data));
+    let puzzle = match puzzle {
+        Ok(Ok(puzzle)) => puzzle,
+        Ok(Err(e)) => return Report {day, parse: Err(format!("invalid input at {e}")), parts: [None, None]},
+        Err(e) => return Report {day, parse: Err(e), parts: [None, None]}
+    };
+    let selected = |p| part.is_none_or(|part| part == p);
+    let part_1 = selected(1).then(|| run_part(puzzle.as_ref(), |p| p.part_1()));
+    let part_2 = selected(2).then(|| run_part(puzzle.as_ref(), |p| p.part_2()));
+    Report {day, parse: Ok(parse), parts: [part_1, part_2]}
+}
+
+/// Duration with a unit suited to its magnitude, e.g. `1.23 ms`.
+pub fn format_duration(duration: Duration) -> String {
+    let ns = duration.as_nanos() as f64;
+    if ns < 1e3 {
+        format!("{ns:.0} ns")
+    } else if ns < 1e6 {
+        format!("{:.2} µs", ns / 1e3)
+    } else if ns < 1e9 {
+        format!("{:.2} ms", ns / 1e6)
+    } else {
+        format!("{:.2} s", ns / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers() {
+        let answers = Answers::parse("part 1: 42\npart 2: a,b\n").unwrap();
+        assert_eq!(answers.0, [Some("42".to_string()), Some("a,b".to_string())]);
+        let answers = Answers::parse("part 1: 42\n").unwrap();
+        assert_eq!(answers.0, [Some("42".to_string()), None]);
+        assert!(Answers::parse("part 3: 42\n").is_none());
+        assert!(Answers::parse("42\n").is_none());
+    }
+
+    #[test]
+    fn test_check() {
+        let part = |answer: &str| Some(Part {status: Status::Ok(answer.to_string()), duration: Duration::ZERO, check: None});
+        let mut report = Report {day: &crate::aoc::days()[0], parse: Ok(Duration::ZERO), parts: [part("42"), part("43")]};
+        assert!(report.is_correct());
+        report.check(&Answers::parse("part 1: 42\npart 2: 44").unwrap());
+        assert!(matches!(report.parts[0].as_ref().unwrap().check, Some(Check::Correct)));
+        assert!(matches!(&report.parts[1].as_ref().unwrap().check, Some(Check::Wrong(e)) if e == "44"));
+        assert!(!report.is_correct());
+    }
+}
@@ -0,0 +1,321 @@
+//! Cheapest path searches over any state space, backed by a binary heap.
+//!
+//! States are whatever the puzzle needs, a position or a position with a
+//! heading, and `successors` gives the states reachable from one with the
+//! cost of each move. [`dijkstra`] and [`astar`] find one cheapest path,
+//! [`dijkstra_all`] keeps all of them.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, hash_map::Entry};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Cheapest way to reach a goal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Found<S, C> {
+    /// Total cost of the path.
+    pub cost: C,
+    /// States from the start to the goal, both included.
+    pub path: Vec<S>
+}
+
+// heap entry, the smallest estimate comes out first
+struct Node<S, C> {
+    estimate: C,
+    cost: C,
+    state: S
+}
+
+impl<S, C: Ord> PartialEq for Node<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<S, C: Ord> Eq for Node<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Node<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Node<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+    }
+}
+
+/// Cheapest path from any of `starts` to a state accepted by `is_goal`.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+/// Same as [`dijkstra`], guided by `heuristic`, which must never overestimate
+/// the cost left from a state to the goal.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>
+{
+    // cheapest known cost of each reached state, with its predecessor
+    let mut reached: HashMap<S, (C, Option<S>)> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    for state in starts {
+        reached.insert(state.clone(), (C::default(), None));
+        heap.push(Node {estimate: heuristic(&state), cost: C::default(), state});
+    }
+    while let Some(Node {cost, state, ..}) = heap.pop() {
+        if cost > reached[&state].0 {
+            continue;
+        }
+        if is_goal(&state) {
+            let mut path = vec![state];
+            while let Some((_, Some(previous))) = reached.get(path.last().unwrap()) {
+                path.push(previous.clone());
+            }
+            path.reverse();
+            return Some(Found {cost, path});
+        }
+        for (next, step) in successors(&state) {
+            let cost = cost + step;
+            match reached.entry(next.clone()) {
+                Entry::Occupied(e) if e.get().0 <= cost => continue,
+                Entry::Occupied(mut e) => {
+                    e.insert((cost, Some(state.clone())));
+                }
+                Entry::Vacant(e) => {
+                    e.insert((cost, Some(state.clone())));
+                }
+            }
+            heap.push(Node {estimate: cost + heuristic(&next), cost, state: next});
+        }
+    }
+    None
+}
+
+/// Every cheapest path to the goals, kept as the cheapest predecessors of
+/// each reached state, see [`dijkstra_all`].
+pub struct Paths<S, C> {
+    cost: C,
+    goals: Vec<S>,
+    reached: HashMap<S, (C, Vec<S>)>
+}
+
+impl<S: Clone + Eq + Hash, C: Copy + Ord> Paths<S, C> {
+    /// Cost of the paths.
+    pub fn cost(&self) -> C {
+        self.cost
+    }
+
+    /// The goals reached at that cost.
+    pub fn goals(&self) -> &[S] {
+        &self.goals
+    }
+
+    /// States just before `state` on a cheapest path to it, none for a start.
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.reached.get(state).map_or(&[], |(_, previous)| previous)
+    }
+
+    /// States on any of the paths.
+    pub fn states(&self) -> HashSet<S> {
+        let mut states: HashSet<S> = self.goals.iter().cloned().collect();
+        let mut todo = self.goals.clone();
+        while let Some(state) = todo.pop() {
+            for previous in self.predecessors(&state) {
+                if states.insert(previous.clone()) {
+                    todo.push(previous.clone());
+                }
+            }
+        }
+        states
+    }
+
+    /// Number of paths, saturating at `u64::MAX`.
+    pub fn count(&self) -> u64 {
+        // predecessors are cheaper, so counting in order of cost sees them first
+        let mut states: Vec<_> = self.states().into_iter().collect();
+        states.sort_by_key(|s| self.reached[s].0);
+        let mut counts: HashMap<S, u64> = HashMap::new();
+        for state in states {
+            let previous = self.predecessors(&state);
+            let count = if previous.is_empty() {
+                1
+            } else {
+                previous.iter().fold(0, |n: u64, p| n.saturating_add(counts[p]))
+            };
+            counts.insert(state, count);
+        }
+        self.goals.iter().fold(0, |n, g| n.saturating_add(counts[g]))
+    }
+
+    /// The paths one by one, each from a start to a goal.
+    pub fn iter(&self) -> PathsIterator<'_, S, C> {
+        PathsIterator {paths: self, goal: 0, stack: Vec::new()}
+    }
+}
+
+/// Paths of a [`Paths`], see [`Paths::iter`].
+pub struct PathsIterator<'a, S, C> {
+    paths: &'a Paths<S, C>,
+    goal: usize,
+    // current path from its goal back, with the index of the predecessor taken
+    stack: Vec<(S, usize)>
+}
+
+impl<S: Clone + Eq + Hash, C: Copy + Ord> Iterator for PathsIterator<'_, S, C> {
+    type Item = Vec<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let paths = self.paths;
+        if self.stack.is_empty() {
+            self.stack.push((paths.goals.get(self.goal)?.clone(), 0));
+            self.goal += 1;
+        }
+        while let Some(previous) = paths.predecessors(&self.stack.last().unwrap().0).first() {
+            self.stack.push((previous.clone(), 0));
+        }
+        let path = self.stack.iter().rev().map(|(s, _)| s.clone()).collect();
+        // backtrack to the last state with another predecessor to take
+        self.stack.pop();
+        while let Some((state, i)) = self.stack.last_mut() {
+            *i += 1;
+            if let Some(previous) = paths.predecessors(state).get(*i) {
+                self.stack.push((previous.clone(), 0));
+                break;
+            }
+            self.stack.pop();
+        }
+        Some(path)
+    }
+}
+
+/// Every cheapest path from any of `starts` to the states accepted by
+/// `is_goal`, moves must cost more than nothing.
+pub fn dijkstra_all<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool
+) -> Option<Paths<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>
+{
+    let mut reached: HashMap<S, (C, Vec<S>)> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    for state in starts {
+        reached.insert(state.clone(), (C::default(), Vec::new()));
+        heap.push(Node {estimate: C::default(), cost: C::default(), state});
+    }
+    let mut best = None;
+    let mut goals = Vec::new();
+    while let Some(Node {cost, state, ..}) = heap.pop() {
+        if best.is_some_and(|best| cost > best) {
+            break;
+        }
+        if cost > reached[&state].0 {
+            continue;
+        }
+        if is_goal(&state) {
+            best = Some(cost);
+            goals.push(state);
+            continue;
+        }
+        for (next, step) in successors(&state) {
+            let cost = cost + step;
+            match reached.entry(next.clone()) {
+                Entry::Occupied(mut e) if e.get().0 == cost => {
+                    e.get_mut().1.push(state.clone());
+                    continue;
+                }
+                Entry::Occupied(e) if e.get().0 < cost => continue,
+                Entry::Occupied(mut e) => {
+                    e.insert((cost, vec![state.clone()]));
+                }
+                Entry::Vacant(e) => {
+                    e.insert((cost, vec![state.clone()]));
+                }
+            }
+            heap.push(Node {estimate: cost, cost, state: next});
+        }
+    }
+    Some(Paths {cost: best?, goals, reached})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a line of states 0..=10, stepping by 1 costs 3 and jumping by 4 costs 5
+    fn successors(s: &i32) -> Vec<(i32, u32)> {
+        [(s + 1, 3), (s - 1, 3), (s + 4, 5)].into_iter().filter(|(s, _)| (0..=10).contains(s)).collect()
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let found = dijkstra([0], successors, |s| *s == 9).unwrap();
+        assert_eq!(found.cost, 13);
+        assert_eq!(found.path.len(), 4);
+        assert_eq!((found.path[0], found.path[3]), (0, 9));
+
+        let found = dijkstra([0], successors, |s| *s == 0).unwrap();
+        assert_eq!(found, Found {cost: 0, path: vec![0]});
+
+        assert!(dijkstra([0], successors, |s| *s == 11).is_none());
+    }
+
+    #[test]
+    fn test_astar() {
+        let found = astar([0], successors, |s| (9 - *s).unsigned_abs(), |s| *s == 9).unwrap();
+        assert_eq!(found.cost, 13);
+
+        let found = astar([8, 0], successors, |s| (10 - *s).unsigned_abs(), |s| *s == 10).unwrap();
+        assert_eq!(found, Found {cost: 6, path: vec![8, 9, 10]});
+    }
+
+    #[test]
+    fn test_dijkstra_all() {
+        let paths = dijkstra_all([0], successors, |s| *s == 8).unwrap();
+        assert_eq!(paths.cost(), 10);
+        assert_eq!(paths.goals(), [8]);
+        assert_eq!(paths.count(), 1);
+        assert_eq!(paths.iter().collect::<Vec<_>>(), [vec![0, 4, 8]]);
+
+        let paths = dijkstra_all([0], successors, |s| *s == 5).unwrap();
+        assert_eq!(paths.cost(), 8);
+        assert_eq!(paths.count(), 2);
+        let mut found: Vec<_> = paths.iter().collect();
+        found.sort();
+        assert_eq!(found, [vec![0, 1, 5], vec![0, 4, 5]]);
+        let mut states: Vec<_> = paths.states().into_iter().collect();
+        states.sort();
+        assert_eq!(states, [0, 1, 4, 5]);
+
+        let paths = dijkstra_all([0], successors, |s| *s == 9).unwrap();
+        assert_eq!((paths.cost(), paths.count()), (13, 3));
+        assert_eq!(paths.iter().count(), 3);
+
+        let paths = dijkstra_all([0], successors, |s| (*s == 3) || (*s == 5)).unwrap();
+        assert_eq!((paths.cost(), paths.goals().len(), paths.count()), (8, 2, 3));
+        assert_eq!(paths.iter().count(), 3);
+
+        assert!(dijkstra_all([0], successors, |s| *s == 11).is_none());
+    }
+}
@@ -1,5 +1,8 @@
+//! Separators between the items of an iterator.
+
 use std::iter::Peekable;
 
+/// Iterator returned by [`SepIterator::sep`].
 pub struct Sep<I, S>
 where
     I: Iterator,
@@ -45,7 +48,9 @@ where
     }
 }
 
+/// Adds [`sep`](SepIterator::sep) to all the iterators.
 pub trait SepIterator<S>: Iterator {
+    /// Inserts `separator` between each pair of items.
     fn sep(self, separator: S) -> Sep<Self, S>
     where
         Self: Sized,
@@ -0,0 +1,8 @@
+//! Advent of Code 2024 solutions and the helpers they share.
+//!
+//! Each day lives in its own module under [`aoc`] with a puzzle type that
+//! implements [`aoc::Solution`], the days are listed by [`aoc::days`] and run
+//! with [`aoc::runner`].
+#![warn(missing_docs)]
+
+pub mod aoc;
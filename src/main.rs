@@ -1,51 +1,425 @@
-mod aoc;
+use std::ops::RangeInclusive;
+
+use aoc_2024::aoc::{self, Day, Solution, day_17, day_24, runner::{self, Answers, Check, Part, Report, Status}};
+
+#[derive(PartialEq)]
+enum Format {
+    Text,
+    Json
+}
+
+struct Options {
+    part: Option<u8>,
+    verify: bool,
+    format: Format
+}
+
+impl Options {
+    fn parts(&self) -> Vec<u8> {
+        self.part.map_or(vec![1, 2], |part| vec![part])
+    }
+}
 
 fn usage(exe: &str) -> ! {
     use std::path::Path;
     let exe = Path::new(exe).file_name().unwrap().to_str().unwrap();
-    println!("usage: {exe} <day (1-25)>");
+    println!("usage: {exe} <day (1-25)> [--part <1|2>] [--verify] [--format <text|json>] [--verbose] [--input <path|->]");
+    println!("       {exe} <all | first-last> [--part <1|2>] [--verify] [--format <text|json>] [--verbose]");
+    println!("       {exe} 17 [--trace] [--break <address>]... [--cycle-limit <n>] [--input <path|->]");
+    println!("       {exe} 24 --dot [--highlight <wire,...>] [--input <path|->]");
+    println!("       {exe} list");
     std::process::exit(0)
 }
 
-fn main() {
-    let args: Vec<_> = std::env::args().collect();
-    if args.len() != 2 {
-        usage(&args[0]);
+fn fail(message: &str) -> ! {
+    eprintln!("error: {message}");
+    std::process::exit(1)
+}
+
+fn input_path(day: u8, input: Option<&str>) -> String {
+    match input {
+        Some(path) => path.to_string(),
+        None => format!("data/day_{day:02}/input.txt")
+    }
+}
+
+fn load_input(day: u8, input: Option<&str>) -> Result<String, String> {
+    match input {
+        Some("-") => {
+            use std::io::Read;
+            let mut data = String::new();
+            match std::io::stdin().read_to_string(&mut data) {
+                Ok(_) => Ok(data),
+                Err(e) => Err(format!("cannot read input from stdin: {e}"))
+            }
+        }
+        input => {
+            let path = input_path(day, input);
+            std::fs::read_to_string(&path).map_err(|e| format!("cannot read input file '{path}': {e}"))
+        }
+    }
+}
+
+// answers of an input file are stored next to it, `answers.txt` for
+// `input.txt` and `test.answers.txt` for `test.txt`, there are none for stdin
+fn answers_path(day: u8, input: Option<&str>) -> Option<std::path::PathBuf> {
+    if input == Some("-") {
+        return None;
+    }
+    let path = std::path::PathBuf::from(input_path(day, input));
+    let name = match path.file_stem()?.to_str()? {
+        "input" => "answers.txt".to_string(),
+        stem => format!("{stem}.answers.txt")
+    };
+    Some(path.with_file_name(name))
+}
+
+fn load_answers(day: u8, input: Option<&str>) -> Option<Answers> {
+    let path = answers_path(day, input)?;
+    let data = std::fs::read_to_string(&path).ok()?;
+    let answers = Answers::parse(&data);
+    if answers.is_none() {
+        eprintln!("warning: ignoring invalid answers file '{}'", path.display());
+    }
+    answers
+}
+
+enum Selection {
+    Day(u8),
+    Days(RangeInclusive<u8>)
+}
+
+fn parse_selection(arg: &str) -> Option<Selection> {
+    let valid = |n: &u8| (1..=25).contains(n);
+    if arg == "all" {
+        return Some(Selection::Days(1..=25));
+    }
+    match arg.split_once('-') {
+        Some((first, last)) => {
+            let (first, last) = (first.parse().ok()?, last.parse().ok()?);
+            if valid(&first) && valid(&last) && (first <= last) {
+                Some(Selection::Days(first..=last))
+            } else {
+                None
+            }
+        }
+        None => {
+            let day = arg.parse().ok()?;
+            if valid(&day) {Some(Selection::Day(day))} else {None}
+        }
+    }
+}
+
+fn print_table(rows: &[Vec<String>]) {
+    let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let widths: Vec<_> = (0..columns).map(|c| {
+        rows.iter().filter_map(|r| r.get(c)).map(|s| s.chars().count()).max().unwrap_or(0)
+    }).collect();
+    for row in rows {
+        let mut line = String::new();
+        for (c, cell) in row.iter().enumerate() {
+            if c > 0 {
+                line.push_str("  ");
+            }
+            // day and durations are right aligned, title and answers left aligned
+            if (c == 0) || (2..=5).contains(&c) {
+                line.push_str(&format!("{cell:>w$}", w = widths[c]));
+            } else {
+                line.push_str(&format!("{cell:<w$}", w = widths[c]));
+            }
+        }
+        println!("{}", line.trim_end());
+    }
+}
+
+fn status_text(status: &Status) -> String {
+    match status {
+        Status::Ok(answer) => answer.clone(),
+        Status::Unimplemented => "not implemented".to_string(),
+        Status::Error(e) => format!("error: {e}")
+    }
+}
+
+fn part_text(part: &Part) -> String {
+    let status = status_text(&part.status);
+    match &part.check {
+        Some(Check::Correct) => format!("{status} ✓"),
+        Some(Check::Wrong(expected)) => format!("{status} ✗ (expected {expected})"),
+        None => status
     }
+}
+
+fn report_row(report: &Report) -> Vec<String> {
+    let mut row = vec![report.day.day.to_string(), report.day.title.to_string()];
+    match &report.parse {
+        Ok(parse) => {
+            row.push(runner::format_duration(*parse));
+            row.extend(report.parts.iter().map(|p| match p {
+                Some(p) => runner::format_duration(p.duration),
+                None => "-".to_string()
+            }));
+            row.push(runner::format_duration(report.total()));
+            row.extend(report.parts.iter().map(|p| match p {
+                Some(p) => part_text(p),
+                None => "-".to_string()
+            }));
+        }
+        Err(e) => {
+            row.extend(["-", "-", "-", "-"].map(String::from));
+            row.push(format!("error: {e}"));
+        }
+    }
+    row
+}
 
-    let day: u8 = match args[1].parse() {
-        Ok(n) if (1..=25).contains(&n) => n,
-        _ => {
-            usage(&args[0]);
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c)
         }
+    }
+    json.push('"');
+    json
+}
+
+// one object per part, `run` is the run of the part or why the day could not run
+fn json_part(day: &Day, part: u8, run: Result<&Part, &str>) -> String {
+    let null = || "null".to_string();
+    let (status, answer, error, duration, check, expected) = match run {
+        Ok(run) => {
+            let (status, answer, error) = match &run.status {
+                Status::Ok(answer) => ("ok", json_string(answer), null()),
+                Status::Unimplemented => ("unimplemented", null(), null()),
+                Status::Error(e) => ("error", null(), json_string(e))
+            };
+            let (check, expected) = match &run.check {
+                Some(Check::Correct) => (json_string("correct"), null()),
+                Some(Check::Wrong(expected)) => (json_string("wrong"), json_string(expected)),
+                None => (null(), null())
+            };
+            (status, answer, error, run.duration.as_nanos().to_string(), check, expected)
+        }
+        Err(e) => ("error", null(), json_string(e), null(), null(), null())
+    };
+    format!(
+        "{{\"day\":{},\"title\":{},\"part\":{part},\"status\":\"{status}\",\"answer\":{answer},\"error\":{error},\"duration_ns\":{duration},\"check\":{check},\"expected\":{expected}}}",
+        day.day, json_string(day.title)
+    )
+}
+
+fn print_json(report: &Report, options: &Options) {
+    match &report.parse {
+        Ok(_) => {
+            for (part, run) in (1..=2).zip(&report.parts) {
+                if let Some(run) = run {
+                    println!("{}", json_part(report.day, part, Ok(run)));
+                }
+            }
+        }
+        Err(e) => {
+            for part in options.parts() {
+                println!("{}", json_part(report.day, part, Err(e)));
+            }
+        }
+    }
+}
+
+fn run_all(days: RangeInclusive<u8>, options: &Options) -> bool {
+    // failures are reported in the table, not through the panic hook
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let mut rows = vec![
+        ["day", "title", "parse", "part 1", "part 2", "total", "answer 1", "answer 2"].map(String::from).to_vec()
+    ];
+    let mut total = std::time::Duration::ZERO;
+    let mut ok = true;
+    for day in days.filter_map(aoc::get) {
+        match load_input(day.day, None) {
+            Ok(data) => {
+                let mut report = runner::run(day, &data, options.part);
+                if let Some(answers) = load_answers(day.day, None) {
+                    report.check(&answers);
+                }
+                total += report.total();
+                ok &= report.is_ok() && (!options.verify || report.is_correct());
+                match options.format {
+                    Format::Text => rows.push(report_row(&report)),
+                    Format::Json => print_json(&report, options)
+                }
+            }
+            Err(e) if options.format == Format::Json => {
+                ok = false;
+                for part in options.parts() {
+                    println!("{}", json_part(day, part, Err(&e)));
+                }
+            }
+            Err(e) => {
+                ok = false;
+                let mut row = vec![day.day.to_string(), day.title.to_string()];
+                row.extend(["-", "-", "-", "-"].map(String::from));
+                row.push(format!("error: {e}"));
+                rows.push(row);
+            }
+        }
+    }
+    if options.format == Format::Text {
+        let mut row = vec![String::new(); 5];
+        row[1] = "total".to_string();
+        row.push(runner::format_duration(total));
+        rows.push(row);
+        print_table(&rows);
+    }
+    ok
+}
+
+// prints the day 24 gates as a DOT graph instead of running the day
+fn print_dot(input: Option<&str>, highlight: &[String]) {
+    let data = load_input(24, input).unwrap_or_else(|e| fail(&e));
+    let puzzle = day_24::Puzzle::parse(&data).unwrap_or_else(|e| fail(&format!("invalid input at {e}")));
+    let highlight: Vec<_> = highlight.iter().map(|w| w.as_str()).collect();
+    print!("{}", puzzle.to_dot(&highlight));
+}
+
+fn run_one(day: u8, input: Option<&str>, options: &Options) -> bool {
+    let data = match load_input(day, input) {
+        Ok(data) => data,
+        Err(e) => fail(&e)
+    };
+    let day = match aoc::get(day) {
+        Some(day) => day,
+        None => fail(&format!("day {day} not implemented"))
     };
+    // failures are reported with the parts, not through the panic hook
+    std::panic::set_hook(Box::new(|_| {}));
+    let mut report = runner::run(day, &data, options.part);
+    if let Some(answers) = load_answers(day.day, input) {
+        report.check(&answers);
+    }
+    match options.format {
+        Format::Text => {
+            if let Err(e) = &report.parse {
+                fail(e);
+            }
+            for (i, part) in report.parts.iter().enumerate() {
+                if let Some(part) = part {
+                    println!("part {}: {}", i + 1, part_text(part));
+                }
+            }
+        }
+        Format::Json => print_json(&report, options)
+    }
+    report.is_ok() && (!options.verify || report.is_correct())
+}
 
-    match day {
-        1  => aoc::day_01::solve(),
-        2  => aoc::day_02::solve(),
-        3  => aoc::day_03::solve(),
-        4  => aoc::day_04::solve(),
-        5  => aoc::day_05::solve(),
-        6  => aoc::day_06::solve(),
-        7  => aoc::day_07::solve(),
-        8  => aoc::day_08::solve(),
-        9  => aoc::day_09::solve(),
-        10 => aoc::day_10::solve(),
-        11 => aoc::day_11::solve(),
-        12 => aoc::day_12::solve(),
-        13 => aoc::day_13::solve(),
-        14 => aoc::day_14::solve(),
-        15 => aoc::day_15::solve(),
-        16 => aoc::day_16::solve(),
-        17 => aoc::day_17::solve(),
-        18 => aoc::day_18::solve(),
-        19 => aoc::day_19::solve(),
-        20 => aoc::day_20::solve(),
-        21 => aoc::day_21::solve(),
-        22 => aoc::day_22::solve(),
-        23 => aoc::day_23::solve(),
-        24 => aoc::day_24::solve(),
-        25 => aoc::day_25::solve(),
-        _ => println!("day {day} not implemented")
+fn main() {
+    let args: Vec<_> = std::env::args().collect();
+
+    let mut selection = None;
+    let mut options = Options {part: None, verify: false, format: Format::Text};
+    let mut input = None;
+    // debugging of the day 17 program, see `day_17::set_debugging`
+    let mut debugging: Option<day_17::Debugging> = None;
+    // wires to highlight in the day 24 graph, when printing it
+    let mut dot: Option<Vec<String>> = None;
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--input" | "-i" => {
+                i += 1;
+                match args.get(i) {
+                    Some(path) => input = Some(path.as_str()),
+                    None => usage(&args[0])
+                }
+            }
+            "--part" | "-p" => {
+                i += 1;
+                options.part = match args.get(i).map(|p| p.as_str()) {
+                    Some("1") => Some(1),
+                    Some("2") => Some(2),
+                    _ => usage(&args[0])
+                };
+            }
+            "--format" | "-f" => {
+                i += 1;
+                options.format = match args.get(i).map(|f| f.as_str()) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    _ => usage(&args[0])
+                };
+            }
+            "--trace" => debugging.get_or_insert_default().trace = true,
+            "--dot" => {
+                dot.get_or_insert_default();
+            }
+            "--highlight" => {
+                i += 1;
+                match args.get(i) {
+                    Some(wires) => dot.get_or_insert_default().extend(wires.split(',').map(String::from)),
+                    None => usage(&args[0])
+                }
+            }
+            "--break" => {
+                i += 1;
+                match args.get(i).and_then(|a| a.parse().ok()) {
+                    Some(address) => debugging.get_or_insert_default().options.breakpoints.push(address),
+                    None => usage(&args[0])
+                }
+            }
+            "--cycle-limit" => {
+                i += 1;
+                match args.get(i).and_then(|n| n.parse().ok()) {
+                    Some(limit) => debugging.get_or_insert_default().options.cycle_limit = Some(limit),
+                    None => usage(&args[0])
+                }
+            }
+            "--verify" => options.verify = true,
+            "--verbose" | "-v" => aoc::set_verbose(true),
+            "list" if selection.is_none() && (args.len() == 2) => {
+                for day in aoc::days() {
+                    println!("{:2}: {}", day.day, day.title);
+                }
+                return;
+            }
+            arg if selection.is_none() => {
+                selection = match parse_selection(arg) {
+                    Some(selection) => Some(selection),
+                    None => usage(&args[0])
+                };
+            }
+            _ => usage(&args[0])
+        }
+        i += 1;
+    }
+    if debugging.is_some() {
+        if !matches!(selection, Some(Selection::Day(17))) {
+            fail("--trace, --break and --cycle-limit only apply to day 17");
+        }
+        aoc::set_verbose(true);
+        day_17::set_debugging(debugging);
+    }
+    if let Some(highlight) = dot {
+        if !matches!(selection, Some(Selection::Day(24))) {
+            fail("--dot and --highlight only apply to day 24");
+        }
+        print_dot(input, &highlight);
+        return;
+    }
+    let ok = match selection {
+        Some(Selection::Day(day)) => run_one(day, input, &options),
+        Some(Selection::Days(days)) => {
+            if input.is_some() {
+                fail("--input can only be used with a single day");
+            }
+            run_all(days, &options)
+        }
+        None => usage(&args[0])
+    };
+    if !ok {
+        std::process::exit(1);
     }
 }
@@ -0,0 +1,84 @@
+use aoc_2024::aoc::{self, Solution, day_01, runner::{self, Answers, Status}};
+
+fn read(day: u8, file: &str) -> Option<String> {
+    let path = format!("{}/data/day_{day:02}/{file}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(path).ok()
+}
+
+// runs a day on its input and compares each part with the stored answers,
+// parts without a stored answer must not be implemented
+fn check(day: u8) {
+    let data = read(day, "input.txt").expect("input file");
+    let answers = read(day, "answers.txt").unwrap_or_default();
+    let answers = Answers::parse(&answers).expect("valid answers file");
+    let report = runner::run(aoc::get(day).expect("registered day"), &data, None);
+    if let Err(e) = &report.parse {
+        panic!("day {day}: {e}");
+    }
+    for (part, run) in (1..=2).zip(&report.parts) {
+        let answer = match &run.as_ref().expect("part run").status {
+            Status::Ok(answer) => Some(answer.as_str()),
+            Status::Unimplemented => None,
+            Status::Error(e) => panic!("day {day} part {part}: {e}")
+        };
+        assert_eq!(answer, answers.get(part), "day {day} part {part}");
+    }
+}
+
+macro_rules! days {
+    ($($name:ident: $day:literal $(, $ignore:literal)?;)*) => {
+        $(
+            #[test]
+            $(#[cfg_attr(debug_assertions, ignore = $ignore)])?
+            fn $name() {
+                check($day);
+            }
+        )*
+    };
+}
+
+days! {
+    day_01: 1;
+    day_02: 2;
+    day_03: 3;
+    day_04: 4;
+    day_05: 5;
+    day_06: 6;
+    day_07: 7;
+    day_08: 8;
+    day_09: 9;
+    day_10: 10;
+    day_11: 11;
+    day_12: 12;
+    day_13: 13;
+    day_14: 14;
+    day_15: 15;
+    day_16: 16;
+    day_17: 17;
+    day_18: 18;
+    day_19: 19;
+    day_20: 20;
+    day_21: 21;
+    day_22: 22, "slow without optimisations";
+    day_23: 23;
+    day_24: 24;
+    day_25: 25;
+}
+
+#[test]
+fn test_solution() {
+    let data = read(1, "test.txt").expect("test file");
+    let puzzle = day_01::Puzzle::parse(&data).expect("valid input");
+    assert_eq!(puzzle.part_1().unwrap().map(|a| a.to_string()), Some("11".to_string()));
+    assert_eq!(puzzle.part_2().unwrap().map(|a| a.to_string()), Some("31".to_string()));
+
+    let e = day_01::Puzzle::parse("1 2\n3").err().expect("invalid input");
+    assert_eq!((e.line, e.column), (2, 2));
+}
+
+#[test]
+fn test_days() {
+    let days: Vec<_> = aoc::days().iter().map(|d| d.day).collect();
+    assert_eq!(days, (1..=25).collect::<Vec<_>>());
+    assert!(aoc::get(26).is_none());
+}
@@ -0,0 +1,85 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc-2024"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("runnable binary")
+}
+
+// runs with `input` on stdin
+fn run_with(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aoc-2024"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("runnable binary");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_input_answers() {
+    // an example input is checked against its own answers, not the puzzle ones
+    let output = run(&["1", "--input", "data/day_01/test.txt", "--verify"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "part 1: 11 ✓\npart 2: 31 ✓\n");
+
+    // and left unchecked without them
+    let output = run(&["5", "--input", "data/day_05/test.txt", "--verify"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "part 1: 143\npart 2: 123\n");
+
+    let output = run(&["1", "--part", "1", "--verify"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "part 1: 2375403 ✓\n");
+}
+
+#[test]
+fn test_errors() {
+    // a fault of the program is an error of the part, without a panic message
+    let output = run_with(&["17", "--input", "-"], "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 7,7\n");
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "part 1: error: invalid combo operand 7 at 00\npart 2: error: the program must end with its only jump, back to 0\n");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
+
+#[test]
+fn test_day_17_debugging() {
+    let output = run(&["17", "--input", "data/day_17/test_1.txt", "--part", "1", "--trace", "--break", "4"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "part 1: 4,6,3,5,6,3,5,2,1,0\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with("00: adv 1 (A >>= 1)  A: 729 B: 0 C: 0 -> A: 364 B: 0 C: 0\n"));
+    assert_eq!(stderr.lines().filter(|l| l.starts_with("breakpoint at 04")).count(), 10);
+
+    let output = run(&["17", "--input", "data/day_17/test_1.txt", "--part", "1", "--cycle-limit", "5"]);
+    assert_eq!(stdout(&output), "part 1: error: still running after 5 instructions\n");
+
+    let output = run(&["1", "--trace"]);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "error: --trace, --break and --cycle-limit only apply to day 17\n");
+}
+
+#[test]
+fn test_day_24_dot() {
+    let input = "x00: 1\ny00: 0\n\nx00 XOR y00 -> z00\nx00 AND y00 -> z01\n";
+    let output = run_with(&["24", "--input", "-", "--highlight", "z01,y00"], input);
+    assert!(output.status.success());
+    let dot = stdout(&output);
+    assert!(dot.starts_with("digraph circuit {\n"));
+    assert!(dot.contains("    \"g1\" -> \"z01\" [label=\"z01\", color=red, fontcolor=red, penwidth=2];\n"));
+    assert!(dot.contains("    \"x00\" -> \"g1\" [label=\"x00\"];\n"));
+    assert_eq!(dot.matches("penwidth=2").count(), 5);
+
+    let output = run(&["1", "--dot"]);
+    assert!(!output.status.success());
+}